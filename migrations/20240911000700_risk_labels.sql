@@ -0,0 +1,11 @@
+-- Confirmed outcomes for payments (fraud vs legit), set after the fact by admins
+CREATE TABLE IF NOT EXISTS risk_labels (
+    payment_id uuid PRIMARY KEY REFERENCES payments(id) ON DELETE CASCADE,
+    label TEXT NOT NULL CHECK (label IN ('fraud', 'legit')),
+    note TEXT,
+    labeled_by TEXT,
+    labeled_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_risk_labels_label_time
+    ON risk_labels (label, labeled_at DESC);
@@ -0,0 +1,26 @@
+-- Structured risk reasons: [{"code": "uncommon_handle", "points": 10.0}, ...]
+ALTER TABLE payments
+    ADD COLUMN IF NOT EXISTS risk_reason_codes JSONB NOT NULL DEFAULT '[]'::jsonb;
+
+-- Backfill from the legacy comma-joined English text using production weights
+UPDATE payments p
+   SET risk_reason_codes = COALESCE((
+        SELECT jsonb_agg(jsonb_build_object('code', m.code, 'points', m.points) ORDER BY m.ord)
+          FROM (VALUES
+                (1, 'high INR amount', 'high_amount', 20.0),
+                (2, 'very large ticket', 'very_large_ticket', 18.0),
+                (3, 'extremely large ticket', 'extremely_large_ticket', 18.0),
+                (4, 'cross-border remittance', 'cross_border', 12.0),
+                (5, 'invalid UPI format', 'invalid_upi', 15.0),
+                (6, 'uncommon UPI handle', 'uncommon_handle', 10.0),
+                (7, 'empty UPI handle', 'empty_handle', 8.0),
+                (8, 'message contains flagged keywords', 'flagged_keywords', 10.0),
+                (9, 'off-hours initiation', 'off_hours', 6.0),
+                (10, 'weekend initiation', 'weekend', 4.0)
+               ) AS m(ord, text, code, points)
+         WHERE string_to_array(p.risk_reasons, ', ') @> ARRAY[m.text]
+       ), '[]'::jsonb)
+ WHERE p.risk_reasons IS NOT NULL AND p.risk_reasons <> '';
+
+CREATE INDEX IF NOT EXISTS idx_payments_risk_reason_codes
+    ON payments USING gin (risk_reason_codes jsonb_path_ops);
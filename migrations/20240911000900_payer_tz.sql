@@ -0,0 +1,3 @@
+-- Payer's browser-reported IANA timezone, used for local-time risk features
+ALTER TABLE payments
+    ADD COLUMN IF NOT EXISTS payer_tz TEXT;
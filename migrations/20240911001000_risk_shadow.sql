@@ -0,0 +1,13 @@
+-- Shadow-mode risk results: candidate rules scored alongside live, never used for decisions
+CREATE TABLE IF NOT EXISTS risk_shadow (
+    payment_id uuid NOT NULL REFERENCES payments(id) ON DELETE CASCADE,
+    rules_version TEXT NOT NULL,
+    score INTEGER NOT NULL,
+    label TEXT NOT NULL,
+    reason_codes JSONB NOT NULL DEFAULT '[]'::jsonb,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (payment_id, rules_version)
+);
+
+CREATE INDEX IF NOT EXISTS idx_risk_shadow_version_time
+    ON risk_shadow (rules_version, created_at DESC);
@@ -0,0 +1,14 @@
+-- Client details for sessions and payments (remote IP, user-agent, browser fingerprint)
+ALTER TABLE sessions
+    ADD COLUMN IF NOT EXISTS client_ip TEXT,
+    ADD COLUMN IF NOT EXISTS user_agent TEXT,
+    ADD COLUMN IF NOT EXISTS device_fp TEXT;
+
+ALTER TABLE payments
+    ADD COLUMN IF NOT EXISTS client_ip TEXT,
+    ADD COLUMN IF NOT EXISTS user_agent TEXT,
+    ADD COLUMN IF NOT EXISTS device_fp TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_payments_device_fp_created_at
+    ON payments (device_fp, created_at DESC)
+    WHERE device_fp IS NOT NULL;
@@ -0,0 +1,3 @@
+-- Payer's IP country (ISO 3166-1 alpha-2) from the offline GeoIP database, if configured
+ALTER TABLE payments
+    ADD COLUMN IF NOT EXISTS ip_country TEXT;
@@ -0,0 +1,5 @@
+-- Payee name lookup via the payment rail: outcome, registered name and typed-name similarity
+ALTER TABLE payments
+    ADD COLUMN IF NOT EXISTS payee_lookup TEXT,
+    ADD COLUMN IF NOT EXISTS payee_registered_name TEXT,
+    ADD COLUMN IF NOT EXISTS payee_name_similarity DOUBLE PRECISION;
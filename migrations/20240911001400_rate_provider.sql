@@ -0,0 +1,3 @@
+-- Which FX source priced the payment (exchangerate.host-live, fallback, static)
+ALTER TABLE payments
+    ADD COLUMN IF NOT EXISTS rate_provider TEXT;
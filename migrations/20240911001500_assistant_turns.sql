@@ -0,0 +1,21 @@
+-- Assistant exchanges, per session when the asker has one. Fallback answers
+-- are reviewed by admins to find gaps in the knowledge base.
+CREATE TABLE IF NOT EXISTS assistant_turns (
+    id BIGSERIAL PRIMARY KEY,
+    session_id uuid REFERENCES sessions(id) ON DELETE CASCADE,
+    payment_id uuid,
+    question TEXT NOT NULL,
+    resolved_question TEXT NOT NULL,
+    answer TEXT NOT NULL,
+    lang TEXT NOT NULL,
+    backend TEXT NOT NULL,
+    fallback BOOLEAN NOT NULL DEFAULT FALSE,
+    confidence DOUBLE PRECISION,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_assistant_turns_session
+    ON assistant_turns (session_id, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_assistant_turns_fallback
+    ON assistant_turns (created_at DESC) WHERE fallback;
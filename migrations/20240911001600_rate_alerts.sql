@@ -0,0 +1,27 @@
+-- Rate alerts: notify a payer once when base->quote crosses a threshold.
+-- Alerts are evaluated against fx_rates rows recorded after they were
+-- created, so fx_rates now records when each row was inserted.
+ALTER TABLE fx_rates
+    ADD COLUMN IF NOT EXISTS recorded_at TIMESTAMPTZ NOT NULL DEFAULT now();
+
+CREATE INDEX IF NOT EXISTS idx_fx_rates_recorded
+    ON fx_rates (recorded_at);
+
+CREATE TABLE IF NOT EXISTS rate_alerts (
+    id uuid PRIMARY KEY,
+    base_currency TEXT NOT NULL,
+    quote_currency TEXT NOT NULL DEFAULT 'INR',
+    threshold DOUBLE PRECISION NOT NULL CHECK (threshold > 0),
+    direction TEXT NOT NULL CHECK (direction IN ('above', 'below')),
+    channel TEXT NOT NULL,
+    contact TEXT NOT NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    triggered_at TIMESTAMPTZ,
+    triggered_rate DOUBLE PRECISION,
+    triggered_rate_id uuid,
+    last_error TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_rate_alerts_active
+    ON rate_alerts (base_currency, quote_currency) WHERE active;
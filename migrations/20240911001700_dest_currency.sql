@@ -0,0 +1,15 @@
+-- Payouts in currencies other than INR. dest_amount/rate_to_dest describe the
+-- receiver side in dest_currency; amount_inr stays as the payout's INR value
+-- for risk scoring and reporting. Existing payments were all INR payouts.
+ALTER TABLE payments
+    ADD COLUMN IF NOT EXISTS dest_currency TEXT NOT NULL DEFAULT 'INR',
+    ADD COLUMN IF NOT EXISTS dest_amount DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS rate_to_dest DOUBLE PRECISION;
+
+UPDATE payments
+   SET dest_amount = amount_inr,
+       rate_to_dest = rate_to_inr
+ WHERE dest_amount IS NULL;
+
+ALTER TABLE payments
+    ALTER COLUMN dest_amount SET NOT NULL;
@@ -0,0 +1,3 @@
+-- Cumulative payer limits sum each payer's recent payments by name
+CREATE INDEX IF NOT EXISTS idx_payments_payer_created_at
+    ON payments (lower(trim(payer_name)), created_at);
@@ -0,0 +1,5 @@
+-- Which scheme a payment pays out over ('upi', 'imps' or 'neft') and when the
+-- receiver is credited. Existing payments all went over UPI.
+ALTER TABLE payments
+    ADD COLUMN IF NOT EXISTS rail TEXT NOT NULL DEFAULT 'upi',
+    ADD COLUMN IF NOT EXISTS settles_at TIMESTAMPTZ;
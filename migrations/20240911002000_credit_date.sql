@@ -0,0 +1,4 @@
+-- The business day the receiver is expected to be credited, in the payout
+-- country, from the rail's cut-off and the source and payout calendars.
+ALTER TABLE payments
+    ADD COLUMN IF NOT EXISTS expected_credit_date DATE;
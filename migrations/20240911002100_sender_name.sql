@@ -0,0 +1,4 @@
+-- The payer's own name. payer_name has always held the receiver's name from
+-- the form, so device reuse across payers is counted on this instead
+ALTER TABLE payments
+    ADD COLUMN IF NOT EXISTS sender_name TEXT;
@@ -0,0 +1,8 @@
+-- Double opt-in for rate alerts: a new alert stays inactive until the link
+-- sent to its contact is opened, and is managed with a separate token.
+-- Only SHA-256 hashes of the tokens are stored. Alerts created before this
+-- have neither token, so they stay as they are but can't be managed.
+ALTER TABLE rate_alerts
+    ADD COLUMN IF NOT EXISTS confirmed_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS confirm_token_hash TEXT,
+    ADD COLUMN IF NOT EXISTS manage_token_hash TEXT;
@@ -0,0 +1,7 @@
+-- Payer caps count payments per device fingerprint and per client IP, no
+-- longer by the name field. The device index comes from client_capture.
+DROP INDEX IF EXISTS idx_payments_payer_created_at;
+
+CREATE INDEX IF NOT EXISTS idx_payments_client_ip_created_at
+    ON payments (client_ip, created_at DESC)
+    WHERE client_ip IS NOT NULL;
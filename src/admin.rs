@@ -0,0 +1,218 @@
+use axum::{routing::{get, post}, Router, extract::{State, Path, Query, Request}, response::{IntoResponse, Response}, middleware::{self, Next}, Json};
+use axum::http::StatusCode;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::AppState;
+use crate::ai::RiskReason;
+use crate::kb::Library;
+
+// Admin API. Disabled (404) unless ADMIN_TOKEN is set; callers must send it
+// in the `x-admin-token` header.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/risk_labels", post(label_payment))
+        .route("/risk_labels/:payment_id", get(get_label))
+        .route("/reports/risk_reasons", get(risk_reason_report))
+        .route("/reports/risk_shadow", get(shadow_report))
+        .route("/kb/reload", post(reload_kb))
+        .route("/reports/assistant_fallbacks", get(assistant_fallbacks))
+        .route("/assistant/sessions/:session_id", get(assistant_session))
+        .route_layer(middleware::from_fn(require_admin))
+}
+
+async fn require_admin(req: Request, next: Next) -> Response {
+    let expected = match std::env::var("ADMIN_TOKEN") {
+        Ok(t) if !t.is_empty() => t,
+        _ => return StatusCode::NOT_FOUND.into_response(),
+    };
+    let supplied = req.headers().get("x-admin-token").and_then(|v| v.to_str().ok()).unwrap_or("");
+    if !same_token(supplied, &expected) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    next.run(req).await
+}
+
+/// Compare tokens in time independent of where they differ: both are hashed
+/// first, so lengths don't leak either.
+fn same_token(supplied: &str, expected: &str) -> bool {
+    let (a, b) = (Sha256::digest(supplied.as_bytes()), Sha256::digest(expected.as_bytes()));
+    a.iter().zip(b.iter()).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn error(status: StatusCode, msg: &str) -> Response {
+    (status, Json(serde_json::json!({ "error": msg }))).into_response()
+}
+
+#[derive(Deserialize)]
+struct LabelReq {
+    payment_id: Uuid,
+    label: String,
+    note: Option<String>,
+    labeled_by: Option<String>,
+}
+
+async fn label_payment(State(state): State<AppState>, Json(req): Json<LabelReq>) -> Response {
+    let label = req.label.trim().to_lowercase();
+    if label != "fraud" && label != "legit" {
+        return error(StatusCode::BAD_REQUEST, "label must be 'fraud' or 'legit'");
+    }
+    match state.db.get_payment(req.payment_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return error(StatusCode::NOT_FOUND, "payment not found"),
+        Err(e) => {
+            tracing::error!(error = %e, "payment lookup failed");
+            return error(StatusCode::INTERNAL_SERVER_ERROR, "database error");
+        }
+    }
+    match state.db.upsert_risk_label(req.payment_id, &label, req.note.as_deref(), req.labeled_by.as_deref()).await {
+        Ok(rec) => Json(rec).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "risk label upsert failed");
+            error(StatusCode::INTERNAL_SERVER_ERROR, "database error")
+        }
+    }
+}
+
+async fn get_label(State(state): State<AppState>, Path(payment_id): Path<Uuid>) -> Response {
+    match state.db.get_risk_label(payment_id).await {
+        Ok(Some(rec)) => Json(rec).into_response(),
+        Ok(None) => error(StatusCode::NOT_FOUND, "no label for payment"),
+        Err(e) => {
+            tracing::error!(error = %e, "risk label lookup failed");
+            error(StatusCode::INTERNAL_SERVER_ERROR, "database error")
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ReasonReportQuery {
+    weeks: Option<i32>,
+    code: Option<String>,
+}
+
+// Weekly hit counts per reason code, e.g. ?weeks=8&code=uncommon_handle
+async fn risk_reason_report(State(state): State<AppState>, Query(q): Query<ReasonReportQuery>) -> Response {
+    let weeks = q.weeks.unwrap_or(8).clamp(1, 104);
+    if let Some(c) = q.code.as_deref() {
+        if RiskReason::from_code(c).is_none() {
+            return error(StatusCode::BAD_REQUEST, "unknown reason code");
+        }
+    }
+    match state.db.risk_reason_weekly_counts(weeks, q.code.as_deref()).await {
+        Ok(rows) => Json(serde_json::json!({ "weeks": weeks, "rows": rows })).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "risk reason report failed");
+            error(StatusCode::INTERNAL_SERVER_ERROR, "database error")
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ShadowReportQuery {
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    version: Option<String>,
+    limit: Option<i64>,
+}
+
+// Live vs shadow divergence over a time range (RFC3339 `from`/`to`).
+async fn shadow_report(State(state): State<AppState>, Query(q): Query<ShadowReportQuery>) -> Response {
+    let version = q.version.as_deref().or(state.shadow_rules.as_ref().map(|r| r.version.as_str()));
+    let limit = q.limit.unwrap_or(100).clamp(1, 1000);
+    let pairs = match state.db.shadow_label_pairs(version, q.from, q.to).await {
+        Ok(p) => p,
+        Err(e) => {
+            tracing::error!(error = %e, "shadow report failed");
+            return error(StatusCode::INTERNAL_SERVER_ERROR, "database error");
+        }
+    };
+    let divergent = match state.db.shadow_divergences(version, q.from, q.to, limit).await {
+        Ok(d) => d,
+        Err(e) => {
+            tracing::error!(error = %e, "shadow divergence query failed");
+            return error(StatusCode::INTERNAL_SERVER_ERROR, "database error");
+        }
+    };
+    let total: i64 = pairs.iter().map(|p| p.payments).sum();
+    let agree: i64 = pairs.iter().filter(|p| p.live_label == p.shadow_label).map(|p| p.payments).sum();
+    let mean_abs = if total == 0 {
+        0.0
+    } else {
+        pairs.iter().map(|p| p.mean_abs_score_delta * p.payments as f64).sum::<f64>() / total as f64
+    };
+    Json(serde_json::json!({
+        "version": version,
+        "from": q.from,
+        "to": q.to,
+        "payments": total,
+        "label_agreement": if total == 0 { None } else { Some(agree as f64 / total as f64) },
+        "divergent_payments": total - agree,
+        "mean_abs_score_delta": mean_abs,
+        "confusion": pairs,
+        "divergent": divergent,
+    })).into_response()
+}
+
+// Re-index the knowledge base after editors change the markdown files.
+async fn reload_kb(State(state): State<AppState>) -> Response {
+    let dir = Library::dir_from_env();
+    match Library::load(&dir) {
+        Ok(fresh) => {
+            let passages = fresh.counts();
+            *state.kb.write().expect("kb lock poisoned") = fresh;
+            tracing::info!(passages = ?passages, dir = %dir, "Knowledge base reloaded");
+            Json(serde_json::json!({ "dir": dir, "passages": passages })).into_response()
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "knowledge base reload failed");
+            error(StatusCode::UNPROCESSABLE_ENTITY, &e.to_string())
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct FallbackReportQuery {
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    lang: Option<String>,
+    limit: Option<i64>,
+}
+
+// Questions the assistant couldn't answer, most asked first: the to-do list
+// for knowledge base editors.
+async fn assistant_fallbacks(State(state): State<AppState>, Query(q): Query<FallbackReportQuery>) -> Response {
+    let limit = q.limit.unwrap_or(100).clamp(1, 1000);
+    match state.db.assistant_fallbacks(q.from, q.to, q.lang.as_deref(), limit).await {
+        Ok(rows) => Json(serde_json::json!({ "from": q.from, "to": q.to, "lang": q.lang, "questions": rows })).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "assistant fallback report failed");
+            error(StatusCode::INTERNAL_SERVER_ERROR, "database error")
+        }
+    }
+}
+
+async fn assistant_session(State(state): State<AppState>, Path(session_id): Path<Uuid>) -> Response {
+    match state.db.assistant_turns(session_id).await {
+        Ok(turns) => Json(serde_json::json!({ "session_id": session_id, "turns": turns })).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "assistant history lookup failed");
+            error(StatusCode::INTERNAL_SERVER_ERROR, "database error")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_must_match_exactly() {
+        assert!(same_token("s3cret-token", "s3cret-token"));
+        assert!(!same_token("s3cret-tokeN", "s3cret-token"));
+        assert!(!same_token("s3cret", "s3cret-token"));
+        assert!(!same_token("", "s3cret-token"));
+    }
+}
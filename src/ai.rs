@@ -1,125 +1,662 @@
-use chrono::{Datelike, Timelike, Utc};
+use chrono::{DateTime, Datelike, Timelike, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::payee;
 
 #[derive(Debug, Clone)]
 pub struct RiskAssessment {
-    pub score: i32,          // 0-100
-    pub label: String,       // low/medium/high
-    pub reasons: Vec<String> // short bullets
+    pub score: i32,              // 0-100
+    pub label: String,           // low/medium/high
+    pub reasons: Vec<ReasonHit>, // contributing reasons, in evaluation order
 }
 
 impl RiskAssessment {
-    pub fn label_for(score: i32) -> &'static str {
+    /// Human-readable reasons joined for display (and the legacy `risk_reasons` column).
+    pub fn summary(&self, lang: &str) -> String {
+        reasons_summary(&self.reasons, lang)
+    }
+}
+
+pub fn reasons_summary(hits: &[ReasonHit], lang: &str) -> String {
+    hits.iter().map(|h| h.code.text(lang)).collect::<Vec<_>>().join(", ")
+}
+
+/// Stable risk reason codes. The snake_case code is persisted and exposed
+/// in APIs, so never rename a variant's code; add new ones instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskReason {
+    HighAmount,
+    VeryLargeTicket,
+    ExtremelyLargeTicket,
+    CrossBorder,
+    InvalidUpi,
+    UncommonHandle,
+    EmptyHandle,
+    FlaggedKeywords,
+    OffHours,
+    Weekend,
+    DeviceManyPayers,
+    CorridorCountryMismatch,
+    PayeeUnverified,
+    PayeeNameMismatch,
+    PayeeNameSuspicious,
+}
+
+impl RiskReason {
+    pub const ALL: &'static [RiskReason] = &[
+        RiskReason::HighAmount,
+        RiskReason::VeryLargeTicket,
+        RiskReason::ExtremelyLargeTicket,
+        RiskReason::CrossBorder,
+        RiskReason::InvalidUpi,
+        RiskReason::UncommonHandle,
+        RiskReason::EmptyHandle,
+        RiskReason::FlaggedKeywords,
+        RiskReason::OffHours,
+        RiskReason::Weekend,
+        RiskReason::DeviceManyPayers,
+        RiskReason::CorridorCountryMismatch,
+        RiskReason::PayeeUnverified,
+        RiskReason::PayeeNameMismatch,
+        RiskReason::PayeeNameSuspicious,
+    ];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            RiskReason::HighAmount => "high_amount",
+            RiskReason::VeryLargeTicket => "very_large_ticket",
+            RiskReason::ExtremelyLargeTicket => "extremely_large_ticket",
+            RiskReason::CrossBorder => "cross_border",
+            RiskReason::InvalidUpi => "invalid_upi",
+            RiskReason::UncommonHandle => "uncommon_handle",
+            RiskReason::EmptyHandle => "empty_handle",
+            RiskReason::FlaggedKeywords => "flagged_keywords",
+            RiskReason::OffHours => "off_hours",
+            RiskReason::Weekend => "weekend",
+            RiskReason::DeviceManyPayers => "device_many_payers",
+            RiskReason::CorridorCountryMismatch => "corridor_country_mismatch",
+            RiskReason::PayeeUnverified => "payee_unverified",
+            RiskReason::PayeeNameMismatch => "payee_name_mismatch",
+            RiskReason::PayeeNameSuspicious => "payee_name_suspicious",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|r| r.code() == code)
+    }
+
+    /// Short human text; `lang` is a BCP 47 primary tag, unknown languages get English.
+    pub fn text(self, lang: &str) -> &'static str {
+        match lang {
+            "hi" => match self {
+                RiskReason::HighAmount => "अधिक INR राशि",
+                RiskReason::VeryLargeTicket => "बहुत बड़ी राशि",
+                RiskReason::ExtremelyLargeTicket => "अत्यधिक बड़ी राशि",
+                RiskReason::CrossBorder => "सीमा-पार प्रेषण",
+                RiskReason::InvalidUpi => "अमान्य UPI प्रारूप",
+                RiskReason::UncommonHandle => "असामान्य UPI हैंडल",
+                RiskReason::EmptyHandle => "खाली UPI हैंडल",
+                RiskReason::FlaggedKeywords => "संदेश में संदिग्ध शब्द",
+                RiskReason::OffHours => "असामान्य समय पर भुगतान",
+                RiskReason::Weekend => "सप्ताहांत पर भुगतान",
+                RiskReason::DeviceManyPayers => "एक ही डिवाइस से कई भुगतानकर्ता नाम",
+                RiskReason::CorridorCountryMismatch => "मुद्रा और भुगतानकर्ता का देश मेल नहीं खाते",
+                RiskReason::PayeeUnverified => "प्राप्तकर्ता का नाम सत्यापित नहीं",
+                RiskReason::PayeeNameMismatch => "प्राप्तकर्ता का नाम मेल नहीं खाता",
+                RiskReason::PayeeNameSuspicious => "प्राप्तकर्ता का नाम संदिग्ध",
+            },
+            "ne" => match self {
+                RiskReason::HighAmount => "उच्च INR रकम",
+                RiskReason::VeryLargeTicket => "धेरै ठूलो रकम",
+                RiskReason::ExtremelyLargeTicket => "अत्यन्त ठूलो रकम",
+                RiskReason::CrossBorder => "सीमापार रेमिट्यान्स",
+                RiskReason::InvalidUpi => "अमान्य UPI ढाँचा",
+                RiskReason::UncommonHandle => "असामान्य UPI ह्यान्डल",
+                RiskReason::EmptyHandle => "खाली UPI ह्यान्डल",
+                RiskReason::FlaggedKeywords => "सन्देशमा शंकास्पद शब्दहरू",
+                RiskReason::OffHours => "असामान्य समयमा भुक्तानी",
+                RiskReason::Weekend => "सप्ताहान्तमा भुक्तानी",
+                RiskReason::DeviceManyPayers => "एउटै उपकरणबाट धेरै भुक्तानीकर्ता",
+                RiskReason::CorridorCountryMismatch => "मुद्रा र भुक्तानीकर्ताको देश मेल खाँदैनन्",
+                RiskReason::PayeeUnverified => "प्रापकको नाम प्रमाणित हुन सकेन",
+                RiskReason::PayeeNameMismatch => "प्रापकको नाम मेल खाँदैन",
+                RiskReason::PayeeNameSuspicious => "प्रापकको नाम शंकास्पद",
+            },
+            "si" => match self {
+                RiskReason::HighAmount => "ඉහළ INR මුදල",
+                RiskReason::VeryLargeTicket => "ඉතා විශාල මුදලක්",
+                RiskReason::ExtremelyLargeTicket => "අතිශය විශාල මුදලක්",
+                RiskReason::CrossBorder => "දේශසීමා හරහා ප්‍රේෂණය",
+                RiskReason::InvalidUpi => "වලංගු නොවන UPI ආකෘතිය",
+                RiskReason::UncommonHandle => "අසාමාන්‍ය UPI හැන්ඩලය",
+                RiskReason::EmptyHandle => "හිස් UPI හැන්ඩලය",
+                RiskReason::FlaggedKeywords => "පණිවිඩයේ සැක සහිත වචන",
+                RiskReason::OffHours => "අසාමාන්‍ය වේලාවක ගෙවීම",
+                RiskReason::Weekend => "සති අන්තයේ ගෙවීම",
+                RiskReason::DeviceManyPayers => "එකම උපාංගයෙන් ගෙවන්නන් රැසක්",
+                RiskReason::CorridorCountryMismatch => "මුදල් වර්ගය ගෙවන්නාගේ රටට නොගැලපේ",
+                RiskReason::PayeeUnverified => "ලබන්නාගේ නම තහවුරු කළ නොහැකි විය",
+                RiskReason::PayeeNameMismatch => "ලබන්නාගේ නම නොගැලපේ",
+                RiskReason::PayeeNameSuspicious => "සැක සහිත ලබන්නාගේ නම",
+            },
+            "ar" => match self {
+                RiskReason::HighAmount => "مبلغ مرتفع بالروبية الهندية",
+                RiskReason::VeryLargeTicket => "مبلغ كبير جدًا",
+                RiskReason::ExtremelyLargeTicket => "مبلغ كبير للغاية",
+                RiskReason::CrossBorder => "تحويل عبر الحدود",
+                RiskReason::InvalidUpi => "صيغة UPI غير صالحة",
+                RiskReason::UncommonHandle => "معرّف UPI غير مألوف",
+                RiskReason::EmptyHandle => "معرّف UPI فارغ",
+                RiskReason::FlaggedKeywords => "الرسالة تحتوي على كلمات مشبوهة",
+                RiskReason::OffHours => "دفع في وقت غير معتاد",
+                RiskReason::Weekend => "دفع في عطلة نهاية الأسبوع",
+                RiskReason::DeviceManyPayers => "الجهاز نفسه مع دافعين متعددين",
+                RiskReason::CorridorCountryMismatch => "العملة لا تتوافق مع بلد الدافع",
+                RiskReason::PayeeUnverified => "تعذّر التحقق من اسم المستفيد",
+                RiskReason::PayeeNameMismatch => "اسم المستفيد غير مطابق",
+                RiskReason::PayeeNameSuspicious => "اسم المستفيد مشبوه",
+            },
+            // English, and the fallback for any other language
+            _ => match self {
+                RiskReason::HighAmount => "high INR amount",
+                RiskReason::VeryLargeTicket => "very large ticket",
+                RiskReason::ExtremelyLargeTicket => "extremely large ticket",
+                RiskReason::CrossBorder => "cross-border remittance",
+                RiskReason::InvalidUpi => "invalid UPI format",
+                RiskReason::UncommonHandle => "uncommon UPI handle",
+                RiskReason::EmptyHandle => "empty UPI handle",
+                RiskReason::FlaggedKeywords => "message contains flagged keywords",
+                RiskReason::OffHours => "off-hours initiation",
+                RiskReason::Weekend => "weekend initiation",
+                RiskReason::DeviceManyPayers => "same device, many payer names",
+                RiskReason::CorridorCountryMismatch => "currency unexpected for payer's country",
+                RiskReason::PayeeUnverified => "payee name could not be verified",
+                RiskReason::PayeeNameMismatch => "payee name does not match",
+                RiskReason::PayeeNameSuspicious => "suspicious payee name",
+            },
+        }
+    }
+}
+
+/// One reason that fired, with the points it added to the score.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReasonHit {
+    pub code: RiskReason,
+    pub points: f64,
+}
+
+/// Inputs to risk scoring, independent of HTTP types so historical
+/// payments can be replayed through the same code path.
+#[derive(Debug, Clone)]
+pub struct RiskInput<'a> {
+    pub upi_id: &'a str,
+    pub src_currency: &'a str,
+    pub amount_inr: f64,
+    pub note: Option<&'a str>,
+    pub at: DateTime<Utc>,
+    /// Payer's IANA timezone as reported by the browser, if known.
+    pub payer_tz: Option<Tz>,
+    /// Distinct payer (sender) names used from this device in the last 24h (0 if no fingerprint).
+    pub device_payer_names: i64,
+    /// Payer's IP country (ISO alpha-2) from GeoIP, if resolved.
+    pub ip_country: Option<&'a str>,
+    /// Outcome of the rail's payee name lookup.
+    pub payee: PayeeCheck<'a>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum PayeeCheck<'a> {
+    /// No lookup was attempted (e.g. historical payments).
+    NotChecked,
+    /// The rail could not resolve the payee (unknown VPA or lookup error).
+    Unverified,
+    /// Registered name found; `similarity` compares it with the name the payer typed.
+    Verified { registered_name: &'a str, similarity: f64 },
+}
+
+fn name_tokens(name: &str) -> Vec<String> {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .collect()
+}
+
+fn within_one_edit(a: &str, b: &str) -> bool {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    if a.len().abs_diff(b.len()) > 1 { return false; }
+    let (mut i, mut j, mut edits) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] { i += 1; j += 1; continue; }
+        edits += 1;
+        if edits > 1 { return false; }
+        match a.len().cmp(&b.len()) {
+            std::cmp::Ordering::Greater => i += 1,
+            std::cmp::Ordering::Less => j += 1,
+            std::cmp::Ordering::Equal => { i += 1; j += 1; }
+        }
+    }
+    edits + (a.len() - i) + (b.len() - j) <= 1
+}
+
+/// Share of the typed name's words found in the registered name (0..1).
+/// Initials ("E. Kumar") and single typos in longer words count as matches.
+pub fn name_similarity(typed: &str, registered: &str) -> f64 {
+    let typed = name_tokens(typed);
+    let reg = name_tokens(registered);
+    if typed.is_empty() || reg.is_empty() { return 0.0; }
+    let matched = typed
+        .iter()
+        .filter(|t| {
+            reg.iter().any(|r| {
+                *t == r
+                    || (t.chars().count() == 1 && r.starts_with(t.as_str()))
+                    || (t.chars().count() >= 5 && within_one_edit(t, r))
+            })
+        })
+        .count();
+    matched as f64 / typed.len() as f64
+}
+
+/// Local conventions for a source-currency corridor.
+pub struct CorridorLocale {
+    pub tz: Tz,
+    pub weekend: &'static [Weekday],
+    /// Countries (ISO alpha-2) where paying in this currency is expected.
+    pub countries: &'static [&'static str],
+}
+
+const EUROZONE: &[&str] = &[
+    "AT", "BE", "HR", "CY", "EE", "FI", "FR", "DE", "GR", "IE", "IT", "LV", "LT", "LU", "MT", "NL", "PT", "SK", "SI", "ES",
+];
+
+const SAT_SUN: &[Weekday] = &[Weekday::Sat, Weekday::Sun];
+
+pub fn corridor_locale(src_currency: &str) -> Option<CorridorLocale> {
+    let (tz, weekend, countries): (Tz, &'static [Weekday], &'static [&'static str]) = match src_currency.to_uppercase().as_str() {
+        "INR" => (chrono_tz::Asia::Kolkata, SAT_SUN, &["IN"]),
+        "AED" => (chrono_tz::Asia::Dubai, SAT_SUN, &["AE"]),
+        "NPR" => (chrono_tz::Asia::Kathmandu, &[Weekday::Sat], &["NP"]),
+        "BTN" => (chrono_tz::Asia::Thimphu, SAT_SUN, &["BT"]),
+        "SGD" => (chrono_tz::Asia::Singapore, SAT_SUN, &["SG"]),
+        "MUR" => (chrono_tz::Indian::Mauritius, SAT_SUN, &["MU"]),
+        "EUR" => (chrono_tz::Europe::Paris, SAT_SUN, EUROZONE),
+        "LKR" => (chrono_tz::Asia::Colombo, SAT_SUN, &["LK"]),
+        _ => return None,
+    };
+    Some(CorridorLocale { tz, weekend, countries })
+}
+
+/// Per-feature score contributions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskWeights {
+    pub high_amount: f64,
+    pub very_large_ticket: f64,
+    pub extremely_large_ticket: f64,
+    pub cross_border: f64,
+    pub invalid_upi: f64,
+    pub uncommon_handle: f64,
+    pub empty_handle: f64,
+    pub flagged_keywords: f64,
+    pub off_hours: f64,
+    pub weekend: f64,
+    pub device_many_payers: f64,
+    pub corridor_country_mismatch: f64,
+    pub payee_unverified: f64,
+    pub payee_name_mismatch: f64,
+    pub payee_name_suspicious: f64,
+}
+
+impl Default for RiskWeights {
+    fn default() -> Self {
+        Self {
+            high_amount: 20.0,
+            very_large_ticket: 18.0,
+            extremely_large_ticket: 18.0,
+            cross_border: 12.0,
+            invalid_upi: 15.0,
+            uncommon_handle: 10.0,
+            empty_handle: 8.0,
+            flagged_keywords: 10.0,
+            off_hours: 6.0,
+            weekend: 4.0,
+            device_many_payers: 15.0,
+            corridor_country_mismatch: 12.0,
+            payee_unverified: 15.0,
+            payee_name_mismatch: 20.0,
+            payee_name_suspicious: 25.0,
+        }
+    }
+}
+
+/// A risk rule set. `Default` is what production runs; candidates are
+/// loaded from JSON (missing fields fall back to the production values).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskRules {
+    pub version: String,
+    pub baseline: f64,
+    pub high_amount_inr: f64,
+    pub very_large_inr: f64,
+    pub extremely_large_inr: f64,
+    /// PSP handles that aren't `uncommon_handle`, matched exactly against the
+    /// part after the @; NPCI account addresses (`<ifsc>.ifsc.npci`) always
+    /// count as known. Production uses the payee parser's handles.
+    pub known_handles: Vec<String>,
+    pub flagged_keywords: Vec<String>,
+    /// Off-hours window in the payer's local time, `[start, end)`; wraps past midnight when start > end.
+    pub off_hours_start: u32,
+    pub off_hours_end: u32,
+    /// Registered payee names containing any of these look like impersonation.
+    pub suspicious_payee_terms: Vec<String>,
+    /// Typed vs registered payee name similarity below this is a mismatch.
+    pub payee_name_min_similarity: f64,
+    /// Flag a device once it has been used with this many distinct payer names in 24h.
+    pub device_payer_names_threshold: i64,
+    pub medium_threshold: i32,
+    pub high_threshold: i32,
+    pub weights: RiskWeights,
+}
+
+impl Default for RiskRules {
+    fn default() -> Self {
+        let flags = ["gift", "lottery", "refund", "crypto", "usdt", "investment", "urgent", "test"]; // demo
+        let payee_terms = ["kyc", "refund", "helpdesk", "customer care", "lottery", "prize", "reward", "support"];
+        Self {
+            version: "production".into(),
+            baseline: 5.0,
+            high_amount_inr: 50_000.0,
+            very_large_inr: 200_000.0,
+            extremely_large_inr: 500_000.0,
+            known_handles: payee::Parser::default().handles().map(str::to_string).collect(),
+            flagged_keywords: flags.iter().map(|s| s.to_string()).collect(),
+            suspicious_payee_terms: payee_terms.iter().map(|s| s.to_string()).collect(),
+            payee_name_min_similarity: 0.5,
+            off_hours_start: 23,
+            off_hours_end: 6,
+            device_payer_names_threshold: 3,
+            medium_threshold: 40,
+            high_threshold: 70,
+            weights: RiskWeights::default(),
+        }
+    }
+}
+
+impl RiskRules {
+    pub fn weight(&self, reason: RiskReason) -> f64 {
+        let w = &self.weights;
+        match reason {
+            RiskReason::HighAmount => w.high_amount,
+            RiskReason::VeryLargeTicket => w.very_large_ticket,
+            RiskReason::ExtremelyLargeTicket => w.extremely_large_ticket,
+            RiskReason::CrossBorder => w.cross_border,
+            RiskReason::InvalidUpi => w.invalid_upi,
+            RiskReason::UncommonHandle => w.uncommon_handle,
+            RiskReason::EmptyHandle => w.empty_handle,
+            RiskReason::FlaggedKeywords => w.flagged_keywords,
+            RiskReason::OffHours => w.off_hours,
+            RiskReason::Weekend => w.weekend,
+            RiskReason::DeviceManyPayers => w.device_many_payers,
+            RiskReason::CorridorCountryMismatch => w.corridor_country_mismatch,
+            RiskReason::PayeeUnverified => w.payee_unverified,
+            RiskReason::PayeeNameMismatch => w.payee_name_mismatch,
+            RiskReason::PayeeNameSuspicious => w.payee_name_suspicious,
+        }
+    }
+
+    /// Load a candidate rule set. Without its own `known_handles` it knows
+    /// the handles `payees` accepts, as production does.
+    pub fn from_file(path: &str, payees: &payee::Parser) -> anyhow::Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("cannot read rules file {}: {}", path, e))?;
+        Self::from_json(&raw, payees).map_err(|e| anyhow::anyhow!("invalid rules file {}: {}", path, e))
+    }
+
+    fn from_json(raw: &str, payees: &payee::Parser) -> serde_json::Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(raw)?;
+        let own_handles = value.get("known_handles").is_some();
+        let mut rules: RiskRules = serde_json::from_value(value)?;
+        if !own_handles {
+            rules.known_handles = payees.handles().map(str::to_string).collect();
+        }
+        Ok(rules)
+    }
+
+    /// Whether `handle` (lowercase, the part after the @) is a known PSP
+    /// handle or an NPCI account address.
+    pub fn is_known_handle(&self, handle: &str) -> bool {
+        if let Some(ifsc) = handle.strip_suffix(".ifsc.npci") {
+            return payee::is_ifsc(&ifsc.to_uppercase());
+        }
+        self.known_handles.iter().any(|k| k == handle)
+    }
+
+    pub fn label_for(&self, score: i32) -> &'static str {
         match score {
-            s if s >= 70 => "high",
-            s if s >= 40 => "medium",
+            s if s >= self.high_threshold => "high",
+            s if s >= self.medium_threshold => "medium",
             _ => "low",
         }
     }
-}
 
-// Lightweight heuristic "AI" risk scoring for demo purposes.
-// Inputs are provided individually to decouple from HTTP types.
-pub fn assess_risk(
-    upi_id: &str,
-    src_currency: &str,
-    amount_inr: f64,
-    note: Option<&str>,
-) -> RiskAssessment {
-    let mut score: f64 = 5.0; // baseline
-    let mut reasons: Vec<String> = Vec::new();
-
-    // Amount-based scaling (nonlinear, with caps)
-    let a = amount_inr.max(0.0);
-    if a > 50_000.0 { score += 20.0; reasons.push("high INR amount".into()); }
-    if a > 200_000.0 { score += 18.0; reasons.push("very large ticket".into()); }
-    if a > 500_000.0 { score += 18.0; reasons.push("extremely large ticket".into()); }
-
-    // Cross-border bump when not INR
-    if src_currency.to_uppercase() != "INR" {
-        score += 12.0;
-        reasons.push("cross-border remittance".into());
-    }
-
-    // UPI ID quality: prefer domain-like handles, penalize unusual suffixes
-    let upi = upi_id.trim();
-    if !upi.contains('@') { score += 15.0; reasons.push("invalid UPI format".into()); }
-    if upi.contains('@') {
-        let parts: Vec<&str> = upi.split('@').collect();
-        if let Some(suffix) = parts.get(1) {
-            let s = suffix.to_lowercase();
-            let known = [
-                "upi","oksbi","okhdfcbank","okicici","oksbi","ybl","ibl","axl","paytm","apl","sbi","rbi","axisbank","ibl"
-            ];
-            if !known.iter().any(|k| s.contains(k)) {
-                score += 10.0; reasons.push("uncommon UPI handle".into());
+    fn is_off_hours(&self, hour: u32) -> bool {
+        if self.off_hours_start > self.off_hours_end {
+            hour >= self.off_hours_start || hour < self.off_hours_end
+        } else {
+            (self.off_hours_start..self.off_hours_end).contains(&hour)
+        }
+    }
+
+    // Lightweight heuristic "AI" risk scoring for demo purposes.
+    pub fn assess(&self, input: &RiskInput) -> RiskAssessment {
+        let mut reasons: Vec<ReasonHit> = Vec::new();
+        let mut hit = |code: RiskReason| reasons.push(ReasonHit { code, points: self.weight(code) });
+
+        // Amount-based scaling (nonlinear, with caps)
+        let a = input.amount_inr.max(0.0);
+        if a > self.high_amount_inr { hit(RiskReason::HighAmount); }
+        if a > self.very_large_inr { hit(RiskReason::VeryLargeTicket); }
+        if a > self.extremely_large_inr { hit(RiskReason::ExtremelyLargeTicket); }
+
+        // Cross-border bump when not INR
+        if input.src_currency.to_uppercase() != "INR" {
+            hit(RiskReason::CrossBorder);
+        }
+
+        // UPI ID quality: prefer domain-like handles, penalize unusual suffixes
+        let upi = input.upi_id.trim();
+        if !upi.contains('@') { hit(RiskReason::InvalidUpi); }
+        if upi.contains('@') {
+            let parts: Vec<&str> = upi.split('@').collect();
+            if let Some(suffix) = parts.get(1) {
+                if !self.is_known_handle(&suffix.to_lowercase()) {
+                    hit(RiskReason::UncommonHandle);
+                }
+            }
+            if parts[0].is_empty() { hit(RiskReason::EmptyHandle); }
+        }
+
+        // Note-based simple NLP flags
+        if let Some(n) = input.note {
+            let nlow = n.to_lowercase();
+            if self.flagged_keywords.iter().any(|k| nlow.contains(k.as_str())) {
+                hit(RiskReason::FlaggedKeywords);
+            }
+        }
+
+        // Payee name verification via the rail
+        match input.payee {
+            PayeeCheck::NotChecked => {}
+            PayeeCheck::Unverified => hit(RiskReason::PayeeUnverified),
+            PayeeCheck::Verified { registered_name, similarity } => {
+                if similarity < self.payee_name_min_similarity { hit(RiskReason::PayeeNameMismatch); }
+                let reg = registered_name.to_lowercase();
+                if self.suspicious_payee_terms.iter().any(|t| reg.contains(t.as_str())) {
+                    hit(RiskReason::PayeeNameSuspicious);
+                }
+            }
+        }
+
+        // Device reuse across payer identities
+        if input.device_payer_names >= self.device_payer_names_threshold {
+            hit(RiskReason::DeviceManyPayers);
+        }
+
+        // GeoIP: currency chosen from a country outside its corridor (e.g. AED from BR)
+        let locale = corridor_locale(input.src_currency);
+        if let (Some(country), Some(l)) = (input.ip_country, locale.as_ref()) {
+            if !l.countries.iter().any(|c| c.eq_ignore_ascii_case(country)) {
+                hit(RiskReason::CorridorCountryMismatch);
             }
         }
-        if parts.get(0).map_or(true, |p| p.is_empty()) { score += 8.0; reasons.push("empty UPI handle".into()); }
+
+        // Time-of-day and weekend effects (demo only), in the payer's local
+        // time (browser-reported, else the corridor's), with corridor weekends
+        let tz = input.payer_tz.or(locale.as_ref().map(|l| l.tz)).unwrap_or(Tz::UTC);
+        let weekend = locale.as_ref().map_or(SAT_SUN, |l| l.weekend);
+        let local = input.at.with_timezone(&tz);
+        if self.is_off_hours(local.hour()) { hit(RiskReason::OffHours); }
+        if weekend.contains(&local.weekday()) { hit(RiskReason::Weekend); }
+
+        // Clamp 0..100
+        let score = self.baseline + reasons.iter().map(|r| r.points).sum::<f64>();
+        let score_i = score.clamp(0.0, 100.0).round() as i32;
+        let label = self.label_for(score_i).to_string();
+        RiskAssessment { score: score_i, label, reasons }
     }
+}
 
-    // Note-based simple NLP flags
-    if let Some(n) = note {
-        let nlow = n.to_lowercase();
-        let flags = ["gift", "lottery", "refund", "crypto", "usdt", "investment", "urgent", "test"]; // demo
-        if flags.iter().any(|k| nlow.contains(k)) {
-            score += 10.0;
-            reasons.push("message contains flagged keywords".into());
-        }
-    }
-
-    // Time-of-day and weekend effects (demo only)
-    let now = Utc::now();
-    let hour = now.hour();
-    let wd = now.weekday().number_from_monday(); // 1..=7
-    if hour < 6 || hour >= 23 { score += 6.0; reasons.push("off-hours initiation".into()); }
-    if wd >= 6 { score += 4.0; reasons.push("weekend initiation".into()); }
-
-    // Clamp 0..100
-    let score_i = score.max(0.0).min(100.0).round() as i32;
-    let label = RiskAssessment::label_for(score_i).to_string();
-    RiskAssessment { score: score_i, label, reasons }
-}
-
-// Very small FAQ-style answerer: keyword scoring over canned content.
-pub fn answer_faq(question: &str) -> String {
-    let q = question.to_lowercase();
-    let entries: &[(&str, &str)] = &[
-        (
-            "fees",
-            "Fees: INR payments have no fees in this demo. Cross-border includes a fixed ₹99 transfer fee and ₹25 platform fee, plus live FX.",
-        ),
-        (
-            "fx rate",
-            "FX: We fetch a live rate (or use a fallback) and compute base→INR. The rate and timestamp are stored per payment for transparency.",
-        ),
-        (
-            "upi",
-            "UPI: This is a simulated flow for demos. In production, integrate with a licensed bank/PSP/PA and verify webhooks before fulfillment.",
-        ),
-        (
-            "production",
-            "Production: Implement order tracking, reconciliation, webhook signature verification, idempotency and retries. Do not ship demo flows to prod.",
-        ),
-        (
-            "env",
-            "Environment: Set DATABASE_URL, FX_API_KEY, PORT, PUBLIC_BASE_URL, and RUST_LOG to run locally. See README for examples.",
-        ),
-        (
-            "risk",
-            "AI Risk: We compute a simple 0–100 risk score with Low/Medium/High label based on amount, cross-border, UPI handle quality, keywords, and time.",
-        ),
-    ];
+/// Production scoring, with the handles `payees` accepts (UPI_HANDLES included).
+pub fn assess_risk(input: &RiskInput, payees: &payee::Parser) -> RiskAssessment {
+    let rules = RiskRules { known_handles: payees.handles().map(str::to_string).collect(), ..RiskRules::default() };
+    rules.assess(input)
+}
+
+// Very small FAQ-style answerer: keyword scoring over canned content, in the
+// payer's language (see `lang`). Developer topics are English-only.
+fn faq_entries(lang: &str) -> &'static [(&'static str, &'static str)] {
+    match lang {
+        "hi" => &[
+            ("शुल्क फीस चार्ज fee", "शुल्क: इस डेमो में INR भुगतान पर कोई शुल्क नहीं है। सीमा-पार भुगतान पर ₹99 ट्रांसफ़र शुल्क और ₹25 प्लेटफ़ॉर्म शुल्क लगता है, साथ में लाइव विनिमय दर।"),
+            ("दर विनिमय एक्सचेंज fx", "विनिमय दर: हम जाँची हुई लाइव दर लेते हैं (पुरानी या असामान्य दर पर भुगतान रुक जाता है) और आपकी मुद्रा से INR की गणना करते हैं। हर भुगतान के साथ दर और उसका समय सहेजा जाता है।"),
+            ("upi यूपीआई", "UPI: यह डेमो के लिए सिम्युलेटेड फ़्लो है। असली पैसे का लेन-देन नहीं होता।"),
+            ("जोखिम धोखाधड़ी risk", "AI जोखिम: हर भुगतान को 0–100 का जोखिम स्कोर और Low/Medium/High लेबल मिलता है — राशि, सीमा-पार, UPI हैंडल, नोट के शब्दों और समय के आधार पर।"),
+        ],
+        "ne" => &[
+            ("शुल्क फी चार्ज fee", "शुल्क: यो डेमोमा INR भुक्तानीमा कुनै शुल्क लाग्दैन। सीमापार भुक्तानीमा ₹99 ट्रान्सफर शुल्क र ₹25 प्लेटफर्म शुल्क लाग्छ, साथै लाइभ विनिमय दर।"),
+            ("दर विनिमय एक्सचेन्ज fx", "विनिमय दर: हामी जाँचिएको लाइभ दर लिन्छौं (पुरानो वा असामान्य दरमा भुक्तानी रोकिन्छ) र तपाईंको मुद्राबाट INR गणना गर्छौं। हरेक भुक्तानीसँग दर र समय सुरक्षित गरिन्छ।"),
+            ("upi युपीआई", "UPI: यो डेमोका लागि सिमुलेटेड प्रक्रिया हो। वास्तविक पैसा सर्दैन।"),
+            ("जोखिम ठगी risk", "AI जोखिम: हरेक भुक्तानीलाई 0–100 को जोखिम स्कोर र Low/Medium/High लेबल दिइन्छ — रकम, सीमापार, UPI ह्यान्डल, नोटका शब्द र समयका आधारमा।"),
+        ],
+        "si" => &[
+            ("ගාස්තු fee", "ගාස්තු: මෙම ආදර්ශනයේ INR ගෙවීම් සඳහා ගාස්තු නැත. දේශසීමා හරහා ගෙවීම් සඳහා ₹99 මාරු ගාස්තුවක් සහ ₹25 වේදිකා ගාස්තුවක් අය වේ, සජීවී විනිමය අනුපාතය සමඟ."),
+            ("අනුපාත විනිමය fx", "විනිමය අනුපාතය: අපි පරීක්ෂා කළ සජීවී අනුපාතයක් ගෙන (පැරණි හෝ අසාමාන්‍ය අනුපාතයකදී ගෙවීම නතර වේ) ඔබේ මුදල් INR බවට ගණනය කරමු. සෑම ගෙවීමක් සමඟම අනුපාතය සහ වේලාව සුරකිනු ලැබේ."),
+            ("upi යූපීඅයි", "UPI: මෙය ආදර්ශන සඳහා අනුකරණය කළ ක්‍රියාවලියකි. සැබෑ මුදල් මාරු නොවේ."),
+            ("අවදානම වංචා risk", "AI අවදානම: සෑම ගෙවීමකටම 0–100 අවදානම් ලකුණක් සහ Low/Medium/High ලේබලයක් ලැබේ — මුදල, දේශසීමා හරහා ගෙවීම, UPI හසුරුව, සටහනේ වචන සහ වේලාව අනුව."),
+        ],
+        "ar" => &[
+            ("رسوم رسم عموله fee", "الرسوم: لا توجد رسوم على المدفوعات بالروبية الهندية (INR) في هذا العرض التجريبي. تتضمن التحويلات الدولية رسوم تحويل ثابتة قدرها ₹99 ورسوم منصة قدرها ₹25، بالإضافة إلى سعر صرف مباشر."),
+            ("سعر صرف fx", "سعر الصرف: نحصل على سعر مباشر بعد فحصه (تتوقف الدفعة إذا كان السعر قديمًا أو غير طبيعي) ونحسب المبلغ من عملتك إلى الروبية الهندية. يُحفظ السعر ووقته مع كل دفعة."),
+            ("upi يوبياي", "UPI: هذا مسار تجريبي محاكى للعروض فقط، ولا تنتقل أموال حقيقية."),
+            ("مخاطر خطر احتيال risk", "المخاطر: تحصل كل دفعة على درجة مخاطر من 0 إلى 100 وتصنيف Low/Medium/High بناءً على المبلغ والتحويل الدولي ومعرّف UPI وكلمات الملاحظة والتوقيت."),
+        ],
+        _ => &[
+            (
+                "fees",
+                "Fees: INR payments have no fees in this demo. Cross-border includes a fixed ₹99 transfer fee and ₹25 platform fee, plus live FX.",
+            ),
+            (
+                "fx rate",
+                "FX: We fetch a live rate, refuse stale or outlier rates (the payment stops rather than guessing), and compute base→INR. The rate and timestamp are stored per payment for transparency.",
+            ),
+            (
+                "upi",
+                "UPI: This is a simulated flow for demos. In production, integrate with a licensed bank/PSP/PA and verify webhooks before fulfillment.",
+            ),
+            (
+                "production",
+                "Production: Implement order tracking, reconciliation, webhook signature verification, idempotency and retries. Do not ship demo flows to prod.",
+            ),
+            (
+                "env",
+                "Environment: Set DATABASE_URL, FX_API_KEY, PORT, PUBLIC_BASE_URL, and RUST_LOG to run locally. See README for examples.",
+            ),
+            (
+                "risk",
+                "AI Risk: We compute a simple 0–100 risk score with Low/Medium/High label based on amount, cross-border, UPI handle quality, keywords, and time.",
+            ),
+        ],
+    }
+}
+
+fn faq_default(lang: &str) -> &'static str {
+    match lang {
+        "hi" => "मैं शुल्क, विनिमय दर, UPI डेमो और AI जोखिम के बारे में मदद कर सकता हूँ।",
+        "ne" => "म शुल्क, विनिमय दर, UPI डेमो र AI जोखिमबारे मद्दत गर्न सक्छु।",
+        "si" => "ගාස්තු, විනිමය අනුපාත, UPI ආදර්ශනය සහ AI අවදානම ගැන මට උදව් කළ හැක.",
+        "ar" => "يمكنني المساعدة في الرسوم وأسعار الصرف وعرض UPI التجريبي والمخاطر.",
+        _ => "I can help with fees, FX, UPI demo vs. prod, env vars, and AI risk in this app.",
+    }
+}
+
+pub fn answer_faq(question: &str, lang: &str) -> String {
+    let q = crate::lang::normalize(question);
+    let entries = faq_entries(lang);
     let mut best = (0usize, 0usize);
     for (i, (k, _)) in entries.iter().enumerate() {
         let mut score = 0usize;
         for token in k.split_whitespace() {
-            if q.contains(token) { score += 1; }
+            if q.contains(&crate::lang::normalize(token)) { score += 1; }
         }
         if score > best.1 { best = (i, score); }
     }
     if best.1 == 0 {
-        return "I can help with fees, FX, UPI demo vs. prod, env vars, and AI risk in this app.".to_string();
+        return faq_default(lang).to_string();
     }
     entries[best.0].1.to_string()
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn candidate_rules_know_the_parsers_handles_unless_they_list_their_own() {
+        let payees = payee::Parser::default();
+        let rules = RiskRules::from_json(r#"{"version": "v2", "weights": {"flagged_keywords": 20}}"#, &payees).unwrap();
+        assert_eq!(rules.version, "v2");
+        assert_eq!(rules.weights.flagged_keywords, 20.0);
+        assert_eq!(rules.known_handles, payees.handles().collect::<Vec<_>>());
+        let rules = RiskRules::from_json(r#"{"known_handles": ["oksbi"]}"#, &payees).unwrap();
+        assert_eq!(rules.known_handles, ["oksbi"]);
+        assert!(RiskRules::from_json(r#"{"baseline": "high"}"#, &payees).is_err());
+    }
+
+    #[test]
+    fn name_similarity_counts_typed_words_found() {
+        assert_eq!(name_similarity("Edison Kumar", "Edison Kumar"), 1.0);
+        assert_eq!(name_similarity("edison  KUMAR", "Edison Kumar"), 1.0);
+        assert_eq!(name_similarity("Kumar", "Edison Kumar"), 1.0);
+        assert_eq!(name_similarity("Edison Sharma", "Edison Kumar"), 0.5);
+        assert_eq!(name_similarity("Priya Sharma", "Edison Kumar"), 0.0);
+    }
+
+    #[test]
+    fn name_similarity_allows_initials_and_one_typo_in_long_words() {
+        assert_eq!(name_similarity("E. Kumar", "Edison Kumar"), 1.0);
+        assert_eq!(name_similarity("Edisn Kumar", "Edison Kumar"), 1.0);
+        assert_eq!(name_similarity("Kumra", "Edison Kumar"), 0.0);
+        // Short words must match exactly
+        assert_eq!(name_similarity("Ravi", "Ravj"), 0.0);
+        // Two typos in one word make it a different word
+        assert_eq!(name_similarity("Adisom Kumar", "Edison Kumar"), 0.5);
+    }
+
+    #[test]
+    fn name_similarity_of_nothing_is_zero() {
+        assert_eq!(name_similarity("", "Edison Kumar"), 0.0);
+        assert_eq!(name_similarity("Edison", " . "), 0.0);
+    }
+}
@@ -0,0 +1,212 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+
+use crate::clock::SharedClock;
+use crate::db::{Db, RateAlert};
+use crate::fx::RateService;
+use crate::notify::Notifier;
+
+// Background job for rate alerts. Every ALERT_POLL_SECS (default 60) it
+// refreshes the rates of currencies with active alerts (recording new
+// fx_rates rows), then fires each alert whose threshold a newly recorded rate
+// crossed. Alerts are one-shot: an alert is deactivated when claimed, and
+// re-armed only if its notification could not be delivered.
+//
+// Subscribing is double opt-in: a new alert is inactive until the link sent
+// to its contact is opened, so the API can't be used to mail strangers. The
+// subscriber gets a separate management token for reading and cancelling it.
+
+pub const DIRECTIONS: &[&str] = &["above", "below"];
+/// Contact channels alerts can be sent to.
+pub const CHANNELS: &[&str] = &["email"];
+/// How long a confirmation link stays valid.
+pub const CONFIRM_TTL: chrono::Duration = chrono::Duration::hours(24);
+
+/// A fresh confirmation or management token (244 random bits, hex).
+pub fn new_token() -> String {
+    format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple())
+}
+
+/// What is stored for a token: its SHA-256 in hex, so the table alone
+/// doesn't confirm or manage anything.
+pub fn token_hash(token: &str) -> String {
+    Sha256::digest(token.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Basic shape check of a contact for `channel`; delivery is the real test.
+pub fn valid_contact(channel: &str, contact: &str) -> bool {
+    match channel {
+        "email" => match contact.split_once('@') {
+            Some((local, domain)) => {
+                !local.is_empty() && domain.contains('.') && !domain.starts_with('.') && !domain.ends_with('.') && !contact.contains(char::is_whitespace)
+            }
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+pub struct AlertJob {
+    pub db: Db,
+    pub rates: Arc<RateService>,
+    pub notifier: Arc<dyn Notifier>,
+    pub clock: SharedClock,
+    pub poll: Duration,
+}
+
+impl AlertJob {
+    pub fn poll_from_env() -> Duration {
+        let secs: u64 = std::env::var("ALERT_POLL_SECS").ok().and_then(|s| s.parse().ok()).filter(|s| *s > 0).unwrap_or(60);
+        Duration::from_secs(secs)
+    }
+
+    pub fn spawn(self) {
+        tokio::spawn(async move {
+            let mut since: Option<DateTime<Utc>> = None;
+            let mut tick = tokio::time::interval(self.poll);
+            loop {
+                tick.tick().await;
+                // Overlap runs slightly so rows committed late are still seen;
+                // fired alerts are inactive, so nothing fires twice
+                let started = self.clock.now() - chrono::Duration::seconds(5);
+                match self.run_once(since).await {
+                    Ok(fired) if fired > 0 => tracing::info!(fired, "rate alerts fired"),
+                    Ok(_) => {}
+                    Err(e) => {
+                        tracing::warn!(error = %e, "rate alert run failed");
+                        continue;
+                    }
+                }
+                since = Some(started);
+            }
+        });
+    }
+
+    /// One evaluation pass over rates recorded after `since` (all rates since
+    /// each alert's creation if None). Returns how many alerts fired.
+    pub async fn run_once(&self, since: Option<DateTime<Utc>>) -> anyhow::Result<usize> {
+        let bases = self.db.active_alert_bases().await?;
+        if bases.is_empty() {
+            return Ok(0);
+        }
+        let bases: Vec<&str> = bases.iter().map(String::as_str).collect();
+        self.rates.rates_to_inr(&bases, self.clock.now()).await;
+
+        let mut fired = 0;
+        for hit in self.db.rate_alert_hits(since).await? {
+            let Some(alert) = self.db.claim_rate_alert(&hit).await? else { continue };
+            let (subject, body) = message(&alert, hit.rate, hit.fetched_at);
+            match self.notifier.send(&alert.channel, &alert.contact, &subject, &body).await {
+                Ok(()) => fired += 1,
+                Err(e) => {
+                    tracing::warn!(error = %e, alert_id = %alert.id, notifier = self.notifier.name(), "rate alert delivery failed; re-armed");
+                    // One alert that can't be re-armed must not stop the others
+                    if let Err(e) = self.db.release_rate_alert(alert.id, &e.to_string()).await {
+                        tracing::error!(error = %e, alert_id = %alert.id, "rate alert could not be re-armed");
+                    }
+                }
+            }
+        }
+        Ok(fired)
+    }
+}
+
+/// The opt-in message sent to a new alert's contact, with the link that
+/// activates it.
+pub fn confirmation_message(alert: &RateAlert, link: &str) -> (String, String) {
+    let pair = format!("{}/{}", alert.base_currency, alert.quote_currency);
+    let subject = format!("Confirm your {} rate alert", pair);
+    let body = format!(
+        "Someone asked GlobalPay to tell this address when 1 {} goes {} {:.4} {}.\n\n\
+         To switch the alert on, open this link within {} hours:\n{}\n\n\
+         If it wasn't you, ignore this message; you won't hear from us again.",
+        alert.base_currency,
+        alert.direction,
+        alert.threshold,
+        alert.quote_currency,
+        CONFIRM_TTL.num_hours(),
+        link,
+    );
+    (subject, body)
+}
+
+fn message(alert: &RateAlert, rate: f64, fetched_at: DateTime<Utc>) -> (String, String) {
+    let pair = format!("{}/{}", alert.base_currency, alert.quote_currency);
+    let subject = format!("{} is {} {:.4}", pair, alert.direction, alert.threshold);
+    let body = format!(
+        "Your rate alert was reached: 1 {} = {:.4} {} (rate quoted {}). You asked to be told when it went {} {:.4}.\n\n\
+         Rates move quickly; check the live quote before paying. This alert has now been switched off.",
+        alert.base_currency,
+        rate,
+        alert.quote_currency,
+        fetched_at.format("%Y-%m-%d %H:%M UTC"),
+        alert.direction,
+        alert.threshold,
+    );
+    (subject, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alert() -> RateAlert {
+        RateAlert {
+            id: uuid::Uuid::nil(),
+            base_currency: "AED".into(),
+            quote_currency: "INR".into(),
+            threshold: 22.75,
+            direction: "above".into(),
+            channel: "email".into(),
+            contact: "priya@example.com".into(),
+            active: false,
+            created_at: "2026-10-19T06:00:00Z".parse().unwrap(),
+            triggered_at: None,
+            triggered_rate: None,
+            triggered_rate_id: None,
+            last_error: None,
+            confirmed_at: None,
+            confirm_token_hash: None,
+            manage_token_hash: None,
+        }
+    }
+
+    #[test]
+    fn tokens_are_long_random_hex() {
+        let (a, b) = (new_token(), new_token());
+        assert_eq!(a.len(), 64);
+        assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn tokens_are_stored_as_their_sha256() {
+        // As Postgres' encode(sha256(...), 'hex') gives, for rows stored that way
+        assert_eq!(token_hash("abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        let token = new_token();
+        assert_eq!(token_hash(&token), token_hash(&token));
+        assert_ne!(token_hash(&token), token);
+        // A confirmation token is no use as the management token, or the reverse
+        assert_ne!(token_hash(&token), token_hash(&new_token()));
+    }
+
+    #[test]
+    fn the_confirmation_message_carries_the_link_and_its_lifetime() {
+        let (subject, body) = confirmation_message(&alert(), "https://pay.example/api/v1/alerts/x/confirm?token=t");
+        assert_eq!(subject, "Confirm your AED/INR rate alert");
+        assert!(body.contains("goes above 22.7500 INR"));
+        assert!(body.contains("within 24 hours:\nhttps://pay.example/api/v1/alerts/x/confirm?token=t"));
+    }
+
+    #[test]
+    fn contacts_must_look_like_email_addresses() {
+        assert!(valid_contact("email", "priya@example.com"));
+        for bad in ["priya", "@example.com", "priya@example", "priya@.com", "priya@example.", "priya @example.com"] {
+            assert!(!valid_contact("email", bad), "{}", bad);
+        }
+        assert!(!valid_contact("sms", "+971501234567"));
+    }
+}
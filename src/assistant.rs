@@ -0,0 +1,286 @@
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::ai;
+use crate::kb::{self, Hit, Library};
+use crate::lang;
+
+// Answer backends for /ask. ASSISTANT_BACKEND picks one of:
+//   keyword   — the canned `ai::answer_faq` matcher
+//   retrieval — BM25 passages from the knowledge base (default)
+//   llm       — an OpenAI-compatible chat-completions server (llama.cpp,
+//               ollama, ...) grounded in the retrieved passages
+// Any backend error falls back to the keyword matcher. Questions arrive with
+// their detected language; answers come from that language's content and
+// fall back to English when it has nothing relevant.
+#[async_trait]
+pub trait AssistantBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn answer(&self, question: &str, lang: &'static str) -> anyhow::Result<Answer>;
+}
+
+#[derive(Debug, Clone)]
+pub struct Answer {
+    pub text: String,
+    pub passages: Vec<Hit>,
+    pub confidence: Option<f64>,
+    /// True when nothing relevant was found and `text` is the stock reply.
+    pub fallback: bool,
+    pub backend: &'static str,
+    /// Language of `text`.
+    pub lang: &'static str,
+}
+
+fn not_found_text(lang: &str) -> &'static str {
+    match lang {
+        "hi" => "माफ़ कीजिए, यह हमारे सहायता लेखों में नहीं मिला। शुल्क, विनिमय दर, UPI, प्राप्तकर्ता नाम जाँच या जोखिम स्कोर के बारे में पूछें।",
+        "ne" => "माफ गर्नुहोस्, यो हाम्रा सहायता लेखहरूमा भेटिएन। शुल्क, विनिमय दर, UPI, प्रापकको नाम जाँच वा जोखिम स्कोरबारे सोध्नुहोस्।",
+        "si" => "සමාවන්න, එය අපගේ උපකාරක ලිපිවල හමු නොවීය. ගාස්තු, විනිමය අනුපාත, UPI, ලබන්නාගේ නම පරීක්ෂාව හෝ අවදානම් ලකුණු ගැන අසන්න.",
+        "ar" => "عذرًا، لم نجد ذلك في مقالات المساعدة. اسأل عن الرسوم أو أسعار الصرف أو UPI أو التحقق من اسم المستلم أو درجات المخاطر.",
+        _ => "Sorry, I didn't find that in our help articles. Try asking about fees, exchange rates, UPI, the receiver name check or risk scores.",
+    }
+}
+
+const TOP_K: usize = 3;
+
+pub struct KeywordBackend;
+
+#[async_trait]
+impl AssistantBackend for KeywordBackend {
+    fn name(&self) -> &'static str {
+        "keyword"
+    }
+
+    async fn answer(&self, question: &str, lang: &'static str) -> anyhow::Result<Answer> {
+        Ok(keyword_answer(question, lang))
+    }
+}
+
+fn keyword_answer(question: &str, lang: &'static str) -> Answer {
+    Answer { text: ai::answer_faq(question, lang), passages: Vec::new(), confidence: None, fallback: false, backend: "keyword", lang }
+}
+
+pub struct RetrievalBackend {
+    kb: Arc<RwLock<Library>>,
+    min_score: f64,
+}
+
+impl RetrievalBackend {
+    pub fn new(kb: Arc<RwLock<Library>>) -> Self {
+        let min_score = std::env::var("KB_MIN_SCORE").ok().and_then(|s| s.parse().ok()).unwrap_or(1.5);
+        Self { kb, min_score }
+    }
+
+    /// Passages scoring at least KB_MIN_SCORE, best first, from `lang`'s
+    /// content or else English, with the language they came from. None when
+    /// no knowledge base is loaded at all.
+    fn retrieve(&self, question: &str, lang: &'static str) -> Option<(&'static str, Vec<Hit>)> {
+        let library = self.kb.read().expect("kb lock poisoned");
+        if library.is_empty() {
+            return None;
+        }
+        for l in [lang, "en"] {
+            let Some(kb) = library.get(l) else { continue };
+            let hits: Vec<Hit> = kb.search(question, TOP_K).into_iter().filter(|h| h.score >= self.min_score).collect();
+            if !hits.is_empty() {
+                return Some((l, hits));
+            }
+        }
+        Some((lang, Vec::new()))
+    }
+}
+
+fn not_found(backend: &'static str, lang: &'static str) -> Answer {
+    Answer { text: not_found_text(lang).to_string(), passages: Vec::new(), confidence: Some(0.0), fallback: true, backend, lang }
+}
+
+#[async_trait]
+impl AssistantBackend for RetrievalBackend {
+    fn name(&self) -> &'static str {
+        "retrieval"
+    }
+
+    async fn answer(&self, question: &str, lang: &'static str) -> anyhow::Result<Answer> {
+        // No knowledge base on disk: keep the canned keyword answers working
+        let Some((found_lang, hits)) = self.retrieve(question, lang) else { return Ok(keyword_answer(question, lang)) };
+        let Some(top) = hits.first() else { return Ok(not_found(self.name(), lang)) };
+        Ok(Answer {
+            text: top.passage.text.clone(),
+            confidence: Some(kb::confidence(top.score)),
+            passages: hits,
+            fallback: false,
+            backend: self.name(),
+            lang: found_lang,
+        })
+    }
+}
+
+/// OpenAI-compatible `/chat/completions` client. The model only ever sees the
+/// retrieved passages; questions with no passage never reach it.
+pub struct LlmBackend {
+    retrieval: RetrievalBackend,
+    http: reqwest::Client,
+    base_url: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+impl LlmBackend {
+    pub fn from_env(kb: Arc<RwLock<Library>>) -> anyhow::Result<Self> {
+        let base_url = std::env::var("LLM_BASE_URL").unwrap_or_else(|_| "http://localhost:11434/v1".to_string());
+        let model = std::env::var("LLM_MODEL").unwrap_or_else(|_| "llama3.1".to_string());
+        let api_key = std::env::var("LLM_API_KEY").ok().filter(|k| !k.trim().is_empty());
+        let timeout: u64 = std::env::var("LLM_TIMEOUT_SECS").ok().and_then(|s| s.parse().ok()).unwrap_or(10);
+        let http = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(timeout.min(3)))
+            .timeout(Duration::from_secs(timeout))
+            .build()?;
+        Ok(Self {
+            retrieval: RetrievalBackend::new(kb),
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model,
+            api_key,
+        })
+    }
+
+    async fn complete(&self, system: &str, question: &str) -> anyhow::Result<String> {
+        let body = serde_json::json!({
+            "model": self.model,
+            "temperature": 0.1,
+            "max_tokens": 300,
+            "messages": [
+                { "role": "system", "content": system },
+                { "role": "user", "content": question },
+            ],
+        });
+        let mut req = self.http.post(format!("{}/chat/completions", self.base_url)).json(&body);
+        if let Some(key) = &self.api_key {
+            req = req.bearer_auth(key);
+        }
+        let resp = req.send().await?;
+        if !resp.status().is_success() {
+            anyhow::bail!("llm http status {}", resp.status());
+        }
+        let v: serde_json::Value = resp.json().await?;
+        let text = v["choices"][0]["message"]["content"]
+            .as_str()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("llm returned no content"))?;
+        Ok(text.to_string())
+    }
+}
+
+fn grounding_prompt(hits: &[Hit], lang: &str) -> String {
+    let mut prompt = format!(
+        "You are the GlobalPay help assistant. Answer in {} in at most three sentences using ONLY the help passages below. \
+         Never state a fee, exchange rate, limit or other figure that is not written in the passages; if the payer asks \
+         for one that isn't there, say you can't quote it and that the payment form shows the live quote. \
+         If the passages don't answer the question, say so.\n\nPassages:\n",
+        lang::name(lang)
+    );
+    for (i, h) in hits.iter().enumerate() {
+        let heading = h.passage.heading.as_deref().map(|s| format!(" — {}", s)).unwrap_or_default();
+        prompt.push_str(&format!("[{}] {}{}: {}\n", i + 1, h.passage.title, heading, h.passage.text));
+    }
+    prompt
+}
+
+#[async_trait]
+impl AssistantBackend for LlmBackend {
+    fn name(&self) -> &'static str {
+        "llm"
+    }
+
+    async fn answer(&self, question: &str, lang: &'static str) -> anyhow::Result<Answer> {
+        let Some((found_lang, hits)) = self.retrieval.retrieve(question, lang) else { return Ok(keyword_answer(question, lang)) };
+        let Some(top) = hits.first() else { return Ok(not_found(self.name(), lang)) };
+        let confidence = Some(kb::confidence(top.score));
+        // Answer in the payer's language even when grounded in English passages
+        let text = self.complete(&grounding_prompt(&hits, lang), question).await?;
+        let grounding: String = hits.iter().map(|h| h.passage.text.as_str()).chain([question]).collect::<Vec<_>>().join(" ");
+        let invented = ungrounded_figures(&text, &grounding);
+        if !invented.is_empty() {
+            // The model quoted figures we never gave it; answer from the passage instead
+            tracing::warn!(figures = ?invented, "LLM answer quoted ungrounded figures; using retrieved passage");
+            return Ok(Answer { text: top.passage.text.clone(), passages: hits, confidence, fallback: false, backend: "retrieval", lang: found_lang });
+        }
+        Ok(Answer { text, passages: hits, confidence, fallback: false, backend: self.name(), lang })
+    }
+}
+
+pub fn from_env(kb: Arc<RwLock<Library>>) -> anyhow::Result<Arc<dyn AssistantBackend>> {
+    let choice = std::env::var("ASSISTANT_BACKEND").unwrap_or_else(|_| "retrieval".to_string());
+    Ok(match choice.trim() {
+        "keyword" => Arc::new(KeywordBackend),
+        "retrieval" => Arc::new(RetrievalBackend::new(kb)),
+        "llm" => Arc::new(LlmBackend::from_env(kb)?),
+        other => anyhow::bail!("unknown ASSISTANT_BACKEND {} (keyword, retrieval or llm)", other),
+    })
+}
+
+/// Ask `backend`, falling back to the keyword matcher if it fails or times out.
+pub async fn answer(backend: &dyn AssistantBackend, question: &str, lang: &'static str) -> Answer {
+    match backend.answer(question, lang).await {
+        Ok(a) => a,
+        Err(e) => {
+            tracing::warn!(backend = backend.name(), error = %e, "Assistant backend failed; using keyword answers");
+            keyword_answer(question, lang)
+        }
+    }
+}
+
+const CURRENCY_MARKS: &[&str] = &[
+    "₹", "$", "€", "£", "%", "inr", "usd", "eur", "gbp", "aed", "npr", "lkr", "btn", "mur", "rs", "rupee", "rupees",
+    "रुपये", "रुपया", "रुपैयाँ", "रु", "रू", "रुपियाँ", "රුපියල්", "රු", "روبيه", "درهم",
+];
+
+// A figure is a number written next to a currency or percent mark, or any
+// decimal ("0.0123"). Plain counts like "step 2" are not checked.
+fn figures(text: &str) -> Vec<String> {
+    let words: Vec<String> = text.split_whitespace().map(lang::normalize).collect();
+    let is_mark = |w: &str| {
+        let w = w.trim_matches(|c: char| c.is_ascii_punctuation() && c != '%' && c != '$');
+        CURRENCY_MARKS.contains(&w)
+    };
+    let mut out = Vec::new();
+    for (i, w) in words.iter().enumerate() {
+        let number: String = w.chars().filter(|c| c.is_ascii_digit() || *c == '.').collect();
+        let number = number.trim_matches('.');
+        if number.is_empty() || !number.chars().any(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        let marked = CURRENCY_MARKS.iter().any(|m| w.contains(m))
+            || number.contains('.')
+            || (i > 0 && is_mark(&words[i - 1]))
+            || words.get(i + 1).is_some_and(|n| is_mark(n));
+        if marked {
+            out.push(normalize_number(number));
+        }
+    }
+    out
+}
+
+fn normalize_number(n: &str) -> String {
+    if n.contains('.') {
+        n.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        n.to_string()
+    }
+}
+
+/// Figures in `answer` that appear nowhere in `grounding`.
+pub fn ungrounded_figures(answer: &str, grounding: &str) -> Vec<String> {
+    let known: Vec<String> = lang::ascii_digits(grounding)
+        .replace(',', "")
+        .split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .map(|n| n.trim_matches('.'))
+        .filter(|n| !n.is_empty())
+        .map(normalize_number)
+        .collect();
+    figures(answer).into_iter().filter(|f| !known.contains(f)).collect()
+}
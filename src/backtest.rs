@@ -0,0 +1,291 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::ai::{PayeeCheck, RiskInput, RiskRules};
+use crate::db::{Db, LabeledPayment};
+use crate::payee;
+
+// Offline backtest: replay historical payments through a candidate rule set
+// and compare against what production stored, using admin fraud labels as
+// ground truth.
+//
+// Usage: globalpay backtest --candidate rules.json [--from DATE] [--to DATE] [--json]
+
+pub struct BacktestArgs {
+    pub candidate: String,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub json: bool,
+}
+
+impl BacktestArgs {
+    pub fn parse(args: &[String]) -> anyhow::Result<Self> {
+        let mut candidate = None;
+        let mut from = None;
+        let mut to = None;
+        let mut json = false;
+        let mut it = args.iter();
+        while let Some(a) = it.next() {
+            match a.as_str() {
+                "--candidate" => candidate = it.next().cloned(),
+                "--from" => from = Some(parse_time(it.next())?),
+                "--to" => to = Some(parse_time(it.next())?),
+                "--json" => json = true,
+                other => anyhow::bail!("unknown backtest argument: {}", other),
+            }
+        }
+        let candidate = candidate.ok_or_else(|| anyhow::anyhow!("--candidate <rules.json> is required"))?;
+        Ok(Self { candidate, from, to, json })
+    }
+}
+
+fn parse_time(raw: Option<&String>) -> anyhow::Result<DateTime<Utc>> {
+    let raw = raw.ok_or_else(|| anyhow::anyhow!("missing date value"))?;
+    if let Ok(dt) = DateTime::parse_from_rfc3339(raw) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    let d = NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("invalid date {} (use YYYY-MM-DD or RFC3339)", raw))?;
+    Ok(d.and_hms_opt(0, 0, 0).expect("midnight").and_utc())
+}
+
+#[derive(Debug, Serialize)]
+pub struct ThresholdMetrics {
+    pub threshold: &'static str,
+    pub model: &'static str,
+    pub flagged: usize,
+    pub true_pos: usize,
+    pub false_pos: usize,
+    pub false_neg: usize,
+    pub precision: Option<f64>,
+    pub recall: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Flip {
+    pub payment_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub production_label: String,
+    pub production_score: i32,
+    pub candidate_label: String,
+    pub candidate_score: i32,
+    pub outcome: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BacktestReport {
+    pub candidate_version: String,
+    pub payments: usize,
+    pub labeled: usize,
+    pub fraud: usize,
+    pub legit: usize,
+    pub metrics: Vec<ThresholdMetrics>,
+    pub flips: Vec<Flip>,
+}
+
+fn label_rank(label: &str) -> u8 {
+    match label {
+        "high" => 2,
+        "medium" => 1,
+        _ => 0,
+    }
+}
+
+fn metrics(threshold: &'static str, model: &'static str, preds: &[(&str, Option<&str>)]) -> ThresholdMetrics {
+    let min_rank = label_rank(threshold);
+    let (mut flagged, mut tp, mut fp, mut fneg) = (0, 0, 0, 0);
+    for (label, outcome) in preds {
+        let hit = label_rank(label) >= min_rank;
+        if hit { flagged += 1; }
+        match (*outcome, hit) {
+            (Some("fraud"), true) => tp += 1,
+            (Some("fraud"), false) => fneg += 1,
+            (Some("legit"), true) => fp += 1,
+            _ => {}
+        }
+    }
+    let ratio = |n: usize, d: usize| if d == 0 { None } else { Some(n as f64 / d as f64) };
+    ThresholdMetrics {
+        threshold,
+        model,
+        flagged,
+        true_pos: tp,
+        false_pos: fp,
+        false_neg: fneg,
+        precision: ratio(tp, tp + fp),
+        recall: ratio(tp, tp + fneg),
+    }
+}
+
+pub fn evaluate(candidate: &RiskRules, rows: &[LabeledPayment]) -> BacktestReport {
+    let mut prod: Vec<(&str, Option<&str>)> = Vec::with_capacity(rows.len());
+    let mut cand_labels: Vec<String> = Vec::with_capacity(rows.len());
+    let mut flips = Vec::new();
+    for p in rows {
+        let replay = candidate.assess(&RiskInput {
+            upi_id: &p.upi_id,
+            src_currency: &p.source_currency,
+            amount_inr: p.amount_inr,
+            note: p.note.as_deref(),
+            at: p.created_at,
+            payer_tz: p.payer_tz.as_deref().and_then(|t| t.parse().ok()),
+            device_payer_names: p.device_payer_names,
+            ip_country: p.ip_country.as_deref(),
+            payee: match (p.payee_lookup.as_deref(), p.payee_registered_name.as_deref()) {
+                (Some("verified"), Some(name)) => PayeeCheck::Verified {
+                    registered_name: name,
+                    similarity: p.payee_name_similarity.unwrap_or(0.0),
+                },
+                (Some(_), _) => PayeeCheck::Unverified,
+                (None, _) => PayeeCheck::NotChecked,
+            },
+        });
+        prod.push((p.risk_label.as_str(), p.outcome.as_deref()));
+        if replay.label != p.risk_label {
+            flips.push(Flip {
+                payment_id: p.id,
+                created_at: p.created_at,
+                production_label: p.risk_label.clone(),
+                production_score: p.risk_score,
+                candidate_label: replay.label.clone(),
+                candidate_score: replay.score,
+                outcome: p.outcome.clone(),
+            });
+        }
+        cand_labels.push(replay.label);
+    }
+    let cand: Vec<(&str, Option<&str>)> = cand_labels
+        .iter()
+        .zip(rows)
+        .map(|(l, p)| (l.as_str(), p.outcome.as_deref()))
+        .collect();
+
+    let mut out = Vec::new();
+    for threshold in ["medium", "high"] {
+        out.push(metrics(threshold, "production", &prod));
+        out.push(metrics(threshold, "candidate", &cand));
+    }
+    let fraud = rows.iter().filter(|p| p.outcome.as_deref() == Some("fraud")).count();
+    let legit = rows.iter().filter(|p| p.outcome.as_deref() == Some("legit")).count();
+    BacktestReport {
+        candidate_version: candidate.version.clone(),
+        payments: rows.len(),
+        labeled: fraud + legit,
+        fraud,
+        legit,
+        metrics: out,
+        flips,
+    }
+}
+
+fn fmt_ratio(v: Option<f64>) -> String {
+    v.map(|x| format!("{:.3}", x)).unwrap_or_else(|| "-".into())
+}
+
+pub fn print_report(r: &BacktestReport) {
+    println!(
+        "Backtest: candidate '{}' vs production over {} payments ({} labeled: {} fraud, {} legit)",
+        r.candidate_version, r.payments, r.labeled, r.fraud, r.legit
+    );
+    println!();
+    println!("{:<10} {:<11} {:>8} {:>5} {:>5} {:>5} {:>10} {:>8}", "threshold", "model", "flagged", "TP", "FP", "FN", "precision", "recall");
+    for m in &r.metrics {
+        println!(
+            "{:<10} {:<11} {:>8} {:>5} {:>5} {:>5} {:>10} {:>8}",
+            m.threshold, m.model, m.flagged, m.true_pos, m.false_pos, m.false_neg, fmt_ratio(m.precision), fmt_ratio(m.recall)
+        );
+    }
+    println!();
+    println!("Flipped payments: {}", r.flips.len());
+    for f in &r.flips {
+        println!(
+            "  {} {} {}({}) -> {}({}) outcome={}",
+            f.payment_id,
+            f.created_at.format("%Y-%m-%d %H:%M"),
+            f.production_label,
+            f.production_score,
+            f.candidate_label,
+            f.candidate_score,
+            f.outcome.as_deref().unwrap_or("-")
+        );
+    }
+}
+
+pub async fn run(db: &Db, args: BacktestArgs) -> anyhow::Result<()> {
+    // Handles as the server accepts them (UPI_HANDLES included)
+    let payees = payee::Parser::from_env()?;
+    let candidate = RiskRules::from_file(&args.candidate, &payees)?;
+    let rows = db.list_labeled_payments(args.from, args.to).await?;
+    let report = evaluate(&candidate, &rows);
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(raw: &[&str]) -> anyhow::Result<BacktestArgs> {
+        BacktestArgs::parse(&raw.iter().map(|a| a.to_string()).collect::<Vec<_>>())
+    }
+
+    fn payment(label: &str, amount_inr: f64, outcome: Option<&str>) -> LabeledPayment {
+        LabeledPayment {
+            id: Uuid::new_v4(),
+            upi_id: "priya.sharma@oksbi".into(),
+            amount_inr,
+            note: None,
+            created_at: "2026-10-19T06:00:00Z".parse().unwrap(),
+            source_currency: "INR".into(),
+            risk_score: 0,
+            risk_label: label.into(),
+            payer_tz: Some("Asia/Kolkata".into()),
+            device_payer_names: 1,
+            ip_country: None,
+            payee_lookup: None,
+            payee_registered_name: None,
+            payee_name_similarity: None,
+            outcome: outcome.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn arguments_need_a_candidate_and_take_dates_or_timestamps() {
+        let parsed = args(&["--candidate", "rules.json", "--from", "2024-09-01", "--to", "2024-10-01T12:00:00+05:30", "--json"]).unwrap();
+        assert_eq!(parsed.candidate, "rules.json");
+        assert_eq!(parsed.from, Some("2024-09-01T00:00:00Z".parse().unwrap()));
+        assert_eq!(parsed.to, Some("2024-10-01T06:30:00Z".parse().unwrap()));
+        assert!(parsed.json);
+        assert!(args(&["--from", "2024-09-01"]).is_err());
+        assert!(args(&["--candidate", "rules.json", "--from", "01/09/2024"]).is_err());
+        assert!(args(&["--candidate", "rules.json", "--verbose"]).is_err());
+    }
+
+    #[test]
+    fn metrics_count_labeled_payments_at_or_above_the_threshold() {
+        let preds = [("high", Some("fraud")), ("medium", Some("legit")), ("low", Some("fraud")), ("high", None)];
+        let m = metrics("medium", "production", &preds);
+        assert_eq!((m.flagged, m.true_pos, m.false_pos, m.false_neg), (3, 1, 1, 1));
+        assert_eq!((m.precision, m.recall), (Some(0.5), Some(0.5)));
+        let m = metrics("high", "production", &[("low", Some("legit"))]);
+        assert_eq!((m.precision, m.recall), (None, None));
+    }
+
+    #[test]
+    fn flips_are_payments_the_candidate_labels_differently() {
+        let rows = [payment("low", 1_000.0, Some("legit")), payment("low", 600_000.0, Some("fraud"))];
+        let strict = RiskRules { version: "strict".into(), medium_threshold: 5, ..RiskRules::default() };
+        let report = evaluate(&strict, &rows);
+        assert_eq!((report.payments, report.labeled, report.fraud, report.legit), (2, 2, 1, 1));
+        assert_eq!(report.candidate_version, "strict");
+        assert_eq!(report.flips.len(), 2);
+        assert!(report.flips.iter().all(|f| f.production_label == "low" && f.candidate_label != "low"));
+        let report = evaluate(&RiskRules::default(), &rows[..1]);
+        assert!(report.flips.is_empty());
+    }
+}
@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+use crate::currency::Registry;
+
+// Business-day calendars for payout settlement, loaded from CALENDAR_DIR
+// (default config/calendars):
+// - one file per country (IN.json, AE.json, EU.json for TARGET2, ...) with
+//   its timezone, weekend days (Sat/Sun, Sat in Nepal), holidays and the
+//   last date its holiday list covers (`valid_until`). A holiday can name the
+//   rails it closes ("neft"); otherwise it closes all of them;
+// - rails.json with each scheme's daily cut-off and whether it credits
+//   domestic payouts around the clock.
+// Currencies name their calendar in the currencies file.
+//
+// The expected credit date of a payout: on an always-open rail, a domestic
+// payout (same source and payout currency) is credited the day the rail
+// settles it. Otherwise a payout made after the scheme's cut-off starts when
+// the rails' next day begins, and rolls forward to a business day in the
+// payout country and, for cross-border payouts, in the source country too.
+// All dates are in the payout country's timezone; only the cut-off is read
+// on the rails' clock.
+
+/// Give up rolling forward after this many days (a misconfigured calendar).
+const MAX_ROLL_DAYS: u32 = 31;
+/// Warn at startup when a holiday list runs out within this many days.
+const EXPIRY_WARNING_DAYS: i64 = 60;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub name: String,
+    /// Schemes it closes ("upi", "imps", "neft"); empty for all.
+    #[serde(default)]
+    pub rails: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct CountryFile {
+    country: String,
+    name: String,
+    timezone: String,
+    weekend: Vec<String>,
+    valid_until: NaiveDate,
+    #[serde(default)]
+    holidays: Vec<Holiday>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Country {
+    pub code: String,
+    pub name: String,
+    pub timezone: Tz,
+    pub weekend: Vec<Weekday>,
+    /// Last day the holiday list covers; later days only know weekends.
+    pub valid_until: NaiveDate,
+    holidays: Vec<Holiday>,
+}
+
+impl Country {
+    /// The holiday closing `rail` on `day`, if any.
+    pub fn holiday(&self, day: NaiveDate, rail: &str) -> Option<&Holiday> {
+        self.holidays.iter().find(|h| h.date == day && (h.rails.is_empty() || h.rails.iter().any(|r| r == rail)))
+    }
+
+    pub fn is_business_day(&self, day: NaiveDate, rail: &str) -> bool {
+        !self.weekend.contains(&day.weekday()) && self.holiday(day, rail).is_none()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RailHours {
+    /// Payouts made after this (in the rails' timezone) start the next day.
+    #[serde(deserialize_with = "hh_mm")]
+    pub cutoff: NaiveTime,
+    /// Credits domestic payouts on any day, weekends and holidays included.
+    #[serde(default)]
+    pub always_open: bool,
+}
+
+fn hh_mm<'de, D: serde::Deserializer<'de>>(de: D) -> Result<NaiveTime, D::Error> {
+    let raw = String::deserialize(de)?;
+    NaiveTime::parse_from_str(raw.trim(), "%H:%M").map_err(|_| serde::de::Error::custom(format!("cut-off {:?} must look like 16:00", raw)))
+}
+
+#[derive(Deserialize)]
+struct RailsFile {
+    timezone: String,
+    rails: HashMap<String, RailHours>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Calendar {
+    countries: HashMap<String, Country>,
+    rails: HashMap<String, RailHours>,
+    /// Where the rails' cut-offs are set (IST for Indian rails).
+    rail_timezone: Tz,
+    /// Calendar code of each currency that has one.
+    currencies: HashMap<String, String>,
+}
+
+impl Calendar {
+    pub fn from_env(currencies: &Registry) -> anyhow::Result<Self> {
+        let dir = std::env::var("CALENDAR_DIR").unwrap_or_else(|_| "config/calendars".to_string());
+        let read = |path: &std::path::Path| std::fs::read_to_string(path).map_err(|e| anyhow::anyhow!("cannot read calendar file {}: {}", path.display(), e));
+
+        let rails_path = std::path::Path::new(&dir).join("rails.json");
+        let rails_file: RailsFile = serde_json::from_str(&read(&rails_path)?).map_err(|e| anyhow::anyhow!("invalid calendar file {}: {}", rails_path.display(), e))?;
+        let rail_timezone = parse_tz(&rails_file.timezone, &rails_path)?;
+
+        let mut countries = HashMap::new();
+        let entries = std::fs::read_dir(&dir).map_err(|e| anyhow::anyhow!("cannot read calendar directory {}: {}", dir, e))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") || path == rails_path {
+                continue;
+            }
+            let file: CountryFile = serde_json::from_str(&read(&path)?).map_err(|e| anyhow::anyhow!("invalid calendar file {}: {}", path.display(), e))?;
+            let weekend = file
+                .weekend
+                .iter()
+                .map(|d| d.parse::<Weekday>().map_err(|_| anyhow::anyhow!("{}: {:?} is not a weekday", path.display(), d)))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            if weekend.len() >= 7 {
+                anyhow::bail!("{}: every day is a weekend", path.display());
+            }
+            for h in &file.holidays {
+                if let Some(r) = h.rails.iter().find(|r| !rails_file.rails.contains_key(*r)) {
+                    anyhow::bail!("{}: holiday {} names unknown rail {:?}", path.display(), h.date, r);
+                }
+                if h.date > file.valid_until {
+                    anyhow::bail!("{}: holiday {} is after valid_until {}", path.display(), h.date, file.valid_until);
+                }
+            }
+            let code = file.country.trim().to_uppercase();
+            let country = Country {
+                code: code.clone(),
+                name: file.name,
+                timezone: parse_tz(&file.timezone, &path)?,
+                weekend,
+                valid_until: file.valid_until,
+                holidays: file.holidays,
+            };
+            if countries.insert(code.clone(), country).is_some() {
+                anyhow::bail!("calendar for {} is defined twice in {}", code, dir);
+            }
+        }
+
+        let mut by_currency = HashMap::new();
+        for code in currencies.sources().iter().map(|c| c.code.clone()).chain(currencies.destinations().iter().map(|c| c.code.clone())) {
+            let Some(cal) = currencies.get(&code).and_then(|c| c.calendar.clone()) else { continue };
+            if !countries.contains_key(&cal) {
+                anyhow::bail!("{}: calendar {} is not in {}", code, cal, dir);
+            }
+            by_currency.insert(code, cal);
+        }
+        Ok(Calendar { countries, rails: rails_file.rails, rail_timezone, currencies: by_currency })
+    }
+
+    /// Log calendars whose holiday list has run out, or will within
+    /// EXPIRY_WARNING_DAYS of `today`: their credit dates then skip only weekends.
+    pub fn warn_if_expiring(&self, today: NaiveDate) {
+        for c in self.countries.values() {
+            if c.valid_until < today {
+                tracing::error!(calendar = %c.code, valid_until = %c.valid_until, "holiday calendar has expired; expected credit dates ignore its holidays");
+            } else if (c.valid_until - today).num_days() <= EXPIRY_WARNING_DAYS {
+                tracing::warn!(calendar = %c.code, valid_until = %c.valid_until, "holiday calendar runs out soon; add the next year's holidays");
+            }
+        }
+    }
+
+    /// Calendar a currency's payouts settle on.
+    pub fn for_currency(&self, code: &str) -> Option<&Country> {
+        self.currencies.get(code).and_then(|c| self.countries.get(c))
+    }
+
+    /// Timezone a `dest` payout is credited in: its calendar's, else the rails'.
+    pub fn timezone_for(&self, dest: &str) -> Tz {
+        self.for_currency(dest).map(|c| c.timezone).unwrap_or(self.rail_timezone)
+    }
+
+    pub fn rail(&self, rail: &str) -> Option<&RailHours> {
+        self.rails.get(rail)
+    }
+
+    /// The day the receiver is credited for a `source` -> `dest` payout over
+    /// `rail`, made at `created_at` and settled by the rail at `settles_at`,
+    /// in the payout country's local date.
+    pub fn credit_date(&self, rail: &str, source: &str, dest: &str, created_at: DateTime<Utc>, settles_at: DateTime<Utc>) -> NaiveDate {
+        let tz = self.timezone_for(dest);
+        let dest_cal = self.for_currency(dest);
+        let mut day = settles_at.with_timezone(&tz).date_naive();
+        let Some(hours) = self.rail(rail) else { return day };
+        let domestic = source == dest;
+        if domestic && hours.always_open {
+            return day;
+        }
+        let made = created_at.with_timezone(&self.rail_timezone);
+        if made.time() >= hours.cutoff {
+            // Held until the rails' next day starts, seen from the payout country
+            let next_day = made
+                .date_naive()
+                .succ_opt()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .and_then(|t| t.and_local_timezone(self.rail_timezone).earliest());
+            if let Some(start) = next_day {
+                day = day.max(start.with_timezone(&tz).date_naive());
+            }
+        }
+        let source_cal = if domestic { None } else { self.for_currency(source) };
+        for _ in 0..MAX_ROLL_DAYS {
+            let open = dest_cal.is_none_or(|c| c.is_business_day(day, rail)) && source_cal.is_none_or(|c| c.is_business_day(day, rail));
+            if open {
+                break;
+            }
+            day = day.succ_opt().unwrap_or(day);
+        }
+        day
+    }
+}
+
+fn parse_tz(name: &str, path: &std::path::Path) -> anyhow::Result<Tz> {
+    name.trim().parse().map_err(|_| anyhow::anyhow!("{}: unknown timezone {:?}", path.display(), name))
+}
+
+/// When the receiver is credited, for the payer, in the payout country's
+/// timezone `tz` (`Calendar::timezone_for`, the one `credit_date` is in): the
+/// rail's own note ("Credited instantly", "Expected by 03:00 IST, 19 Oct")
+/// when that is the credit day, else "Expected on Mon, 19 Oct".
+pub fn credit_note(credit_date: NaiveDate, settles_at: DateTime<Utc>, paid_at: DateTime<Utc>, tz: Tz) -> String {
+    if credit_date > settles_at.with_timezone(&tz).date_naive() {
+        format!("Expected on {}", credit_date.format("%a, %-d %b"))
+    } else {
+        crate::rails::settlement_note(settles_at, paid_at, tz)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::{Asia, Europe};
+
+    fn day(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    fn country(code: &str, timezone: Tz, holidays: &[(&str, &[&str])]) -> (String, Country) {
+        let holidays = holidays
+            .iter()
+            .map(|(date, rails)| Holiday { date: day(date), name: "Holiday".into(), rails: rails.iter().map(|r| r.to_string()).collect() })
+            .collect();
+        let country = Country { code: code.into(), name: code.into(), timezone, weekend: vec![Weekday::Sat, Weekday::Sun], valid_until: day("2027-12-31"), holidays };
+        (code.into(), country)
+    }
+
+    fn hours(cutoff: &str, always_open: bool) -> RailHours {
+        RailHours { cutoff: NaiveTime::parse_from_str(cutoff, "%H:%M").unwrap(), always_open }
+    }
+
+    /// India with Dussehra (all rails) on Tue 20 Oct and a NEFT-only closure
+    /// on Mon 26 Oct, the UAE with a holiday on Tue 27 Oct, and the euro area.
+    fn calendar() -> Calendar {
+        Calendar {
+            countries: [
+                country("IN", Asia::Kolkata, &[("2026-10-20", &[]), ("2026-10-26", &["neft"])]),
+                country("AE", Asia::Dubai, &[("2026-10-27", &[])]),
+                country("EU", Europe::Paris, &[]),
+            ]
+            .into_iter()
+            .collect(),
+            rails: [("upi".to_string(), hours("16:00", true)), ("neft".to_string(), hours("19:00", false))].into_iter().collect(),
+            rail_timezone: Asia::Kolkata,
+            currencies: [("INR", "IN"), ("AED", "AE"), ("EUR", "EU")].iter().map(|(c, k)| (c.to_string(), k.to_string())).collect(),
+        }
+    }
+
+    #[test]
+    fn domestic_payout_on_always_open_rail_is_credited_when_settled() {
+        // 17:30 IST on Dussehra, after the cut-off
+        let made = at("2026-10-20T12:00:00Z");
+        assert_eq!(calendar().credit_date("upi", "INR", "INR", made, made), day("2026-10-20"));
+    }
+
+    #[test]
+    fn cross_border_payout_after_cut_off_rolls_past_the_weekend() {
+        // Fri 16:30 IST; the NEFT-only closure on Monday doesn't stop UPI
+        let made = at("2026-10-23T11:00:00Z");
+        assert_eq!(calendar().credit_date("upi", "AED", "INR", made, made), day("2026-10-26"));
+    }
+
+    #[test]
+    fn rail_specific_holiday_closes_only_that_rail() {
+        // Fri 19:30 IST misses NEFT's cut-off; Monday is closed for NEFT
+        let made = at("2026-10-23T14:00:00Z");
+        let batch = at("2026-10-23T19:00:00Z");
+        assert_eq!(calendar().credit_date("neft", "INR", "INR", made, batch), day("2026-10-27"));
+    }
+
+    #[test]
+    fn source_country_holiday_delays_cross_border_payouts() {
+        let made = at("2026-10-27T05:00:00Z");
+        assert_eq!(calendar().credit_date("upi", "AED", "INR", made, made), day("2026-10-28"));
+        assert_eq!(calendar().credit_date("upi", "INR", "INR", made, made), day("2026-10-27"));
+    }
+
+    #[test]
+    fn cut_off_is_applied_in_the_payout_timezone() {
+        // 19:30 IST Wed is after NEFT's cut-off; the rails' next day starts
+        // at 20:30 the same evening in Paris, so the Paris date doesn't move
+        let made = at("2026-10-21T14:00:00Z");
+        assert_eq!(calendar().credit_date("neft", "AED", "EUR", made, made), day("2026-10-21"));
+    }
+
+    #[test]
+    fn unknown_rail_uses_the_settlement_date() {
+        let made = at("2026-10-24T20:00:00Z");
+        assert_eq!(calendar().credit_date("swift", "AED", "INR", made, made), day("2026-10-25"));
+    }
+
+    #[test]
+    fn credit_note_compares_dates_in_the_given_timezone() {
+        let paid = at("2026-10-18T14:00:00Z");
+        let settles = at("2026-10-18T20:30:00Z");
+        // 00:30 on the 19th in Dubai, 02:00 on the 19th in India
+        assert_eq!(credit_note(day("2026-10-19"), settles, paid, Asia::Dubai), "Expected by 00:30 +04, 19 Oct");
+        assert_eq!(credit_note(day("2026-10-19"), settles, paid, Asia::Kolkata), "Expected by 02:00 IST, 19 Oct");
+        assert_eq!(credit_note(day("2026-10-20"), settles, paid, Asia::Kolkata), "Expected on Tue, 20 Oct");
+        assert_eq!(credit_note(day("2026-10-18"), paid, paid, Asia::Kolkata), "Credited instantly");
+    }
+}
@@ -0,0 +1,161 @@
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{async_trait, extract::{ConnectInfo, FromRequestParts}, http::request::Parts};
+
+// Who is on the other end of a request: remote IP (honouring X-Forwarded-For
+// only when the peer is a trusted proxy) and user-agent.
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub ip: Option<IpAddr>,
+    pub user_agent: Option<String>,
+}
+
+impl ClientInfo {
+    pub fn ip_string(&self) -> Option<String> {
+        self.ip.map(|ip| ip.to_string())
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ClientInfo {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let peer = parts.extensions.get::<ConnectInfo<SocketAddr>>().map(|ci| ci.0.ip());
+        let forwarded = parts
+            .headers
+            .get("x-forwarded-for")
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.split(',').filter_map(|p| p.trim().parse::<IpAddr>().ok()).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let real_ip = parts
+            .headers
+            .get("x-real-ip")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|s| s.trim().parse::<IpAddr>().ok());
+        let ip = peer.map(|p| resolve_client_ip(p, &forwarded, real_ip, &trusted_proxies()));
+        let user_agent = parts
+            .headers
+            .get(axum::http::header::USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.chars().take(512).collect());
+        Ok(ClientInfo { ip, user_agent })
+    }
+}
+
+/// An IP network in CIDR form; a bare address is a /32 (or /128).
+#[derive(Debug, Clone, Copy)]
+pub struct IpNet {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpNet {
+    pub fn parse(raw: &str) -> Option<Self> {
+        let (addr, prefix) = match raw.split_once('/') {
+            Some((a, p)) => (a.trim().parse::<IpAddr>().ok()?, Some(p.trim().parse::<u8>().ok()?)),
+            None => (raw.trim().parse::<IpAddr>().ok()?, None),
+        };
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max);
+        if prefix > max { return None; }
+        Some(Self { addr, prefix })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = if self.prefix == 0 { 0 } else { u32::MAX << (32 - self.prefix) };
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = if self.prefix == 0 { 0 } else { u128::MAX << (128 - self.prefix) };
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+// TRUSTED_PROXIES="127.0.0.1,10.0.0.0/8" — peers allowed to set forwarding headers.
+fn trusted_proxies() -> Vec<IpNet> {
+    std::env::var("TRUSTED_PROXIES")
+        .ok()
+        .map(|s| s.split(',').filter_map(IpNet::parse).collect())
+        .unwrap_or_default()
+}
+
+/// Walk X-Forwarded-For from the right, skipping trusted hops; the first
+/// untrusted address is the client. Untrusted peers' headers are ignored.
+pub fn resolve_client_ip(peer: IpAddr, forwarded: &[IpAddr], real_ip: Option<IpAddr>, trusted: &[IpNet]) -> IpAddr {
+    let is_trusted = |ip: IpAddr| trusted.iter().any(|n| n.contains(ip));
+    if !is_trusted(peer) {
+        return peer;
+    }
+    for hop in forwarded.iter().rev() {
+        if !is_trusted(*hop) {
+            return *hop;
+        }
+    }
+    forwarded.first().copied().or(real_ip).unwrap_or(peer)
+}
+
+/// Accept the browser-submitted fingerprint only if it looks like our hex hash.
+pub fn sanitize_fingerprint(raw: Option<&str>) -> Option<String> {
+    let fp = raw?.trim().to_ascii_lowercase();
+    if fp.is_empty() || fp.len() > 64 || !fp.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(fp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    fn nets(list: &[&str]) -> Vec<IpNet> {
+        list.iter().map(|n| IpNet::parse(n).unwrap()).collect()
+    }
+
+    #[test]
+    fn untrusted_peers_are_the_client_whatever_their_headers_say() {
+        let trusted = nets(&["10.0.0.0/8"]);
+        assert_eq!(resolve_client_ip(ip("203.0.113.7"), &[ip("198.51.100.1")], Some(ip("198.51.100.2")), &trusted), ip("203.0.113.7"));
+        assert_eq!(resolve_client_ip(ip("203.0.113.7"), &[ip("198.51.100.1")], None, &[]), ip("203.0.113.7"));
+    }
+
+    #[test]
+    fn forwarded_for_is_walked_from_the_right_past_trusted_hops() {
+        let trusted = nets(&["127.0.0.1", "10.0.0.0/8"]);
+        // client, spoofed by the client, then our own proxies
+        let chain = [ip("1.2.3.4"), ip("198.51.100.9"), ip("10.1.2.3")];
+        assert_eq!(resolve_client_ip(ip("127.0.0.1"), &chain, None, &trusted), ip("198.51.100.9"));
+    }
+
+    #[test]
+    fn all_trusted_falls_back_to_the_first_hop_then_real_ip() {
+        let trusted = nets(&["10.0.0.0/8"]);
+        assert_eq!(resolve_client_ip(ip("10.0.0.1"), &[ip("10.0.0.5"), ip("10.0.0.6")], None, &trusted), ip("10.0.0.5"));
+        assert_eq!(resolve_client_ip(ip("10.0.0.1"), &[], Some(ip("203.0.113.7")), &trusted), ip("203.0.113.7"));
+        assert_eq!(resolve_client_ip(ip("10.0.0.1"), &[], None, &trusted), ip("10.0.0.1"));
+    }
+
+    #[test]
+    fn ipv6_proxies_and_clients() {
+        let trusted = nets(&["fd00::/8"]);
+        assert_eq!(resolve_client_ip(ip("fd00::1"), &[ip("2001:db8::7")], None, &trusted), ip("2001:db8::7"));
+        assert!(!trusted[0].contains(ip("10.0.0.1")));
+    }
+
+    #[test]
+    fn network_parsing() {
+        assert!(IpNet::parse("10.0.0.0/33").is_none());
+        assert!(IpNet::parse("not-an-ip").is_none());
+        assert!(IpNet::parse("0.0.0.0/0").unwrap().contains(ip("203.0.113.7")));
+        assert!(!IpNet::parse("10.0.0.0/8").unwrap().contains(ip("11.0.0.1")));
+    }
+}
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+
+// Time source shared through AppState so risk scoring and session expiry
+// can be pinned to a known instant (GP_FIXED_NOW) for demos and replays.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+pub type SharedClock = Arc<dyn Clock>;
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+pub fn from_env() -> anyhow::Result<SharedClock> {
+    match std::env::var("GP_FIXED_NOW") {
+        Ok(raw) if !raw.trim().is_empty() => {
+            let at = DateTime::parse_from_rfc3339(raw.trim())
+                .map_err(|e| anyhow::anyhow!("GP_FIXED_NOW must be RFC3339: {}", e))?
+                .with_timezone(&Utc);
+            tracing::warn!(%at, "Using fixed clock");
+            Ok(Arc::new(FixedClock(at)))
+        }
+        _ => Ok(Arc::new(SystemClock)),
+    }
+}
@@ -0,0 +1,282 @@
+use std::collections::{BTreeSet, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::pricing::Precision;
+
+// The currencies GlobalPay knows, with their ISO 4217 metadata, amount limits
+// and the currencies each can pay out in. Loaded from CURRENCIES_FILE
+// (default config/currencies.json); forms, validation, rounding and the
+// optimizer all read it. Disabled currencies stay known (e.g. USD as a
+// reference currency) but can't be paid from or to.
+//
+// ALLOWED_CURRENCIES ("INR,AED,...") narrows which currencies payers may pay
+// from, and CORRIDORS ("AED>INR,INR>NPR,...") replaces the file's payout lists.
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Currency {
+    /// ISO 4217 code, e.g. "AED".
+    pub code: String,
+    /// ISO 4217 name, e.g. "UAE dirham".
+    pub name: String,
+    /// Where payers are shown it as coming from, e.g. "UAE".
+    pub country: String,
+    /// Business-day calendar payouts to and from it settle on, e.g. "AE"
+    /// (a file in CALENDAR_DIR).
+    #[serde(default)]
+    pub calendar: Option<String>,
+    pub flag: String,
+    pub symbol: String,
+    /// Decimal places amounts are rounded to (2 for cents, 0 for yen).
+    pub minor_units: u32,
+    #[serde(default = "enabled_default")]
+    pub enabled: bool,
+    /// Smallest and largest amount one payment may send in this currency.
+    pub min_amount: f64,
+    pub max_amount: f64,
+    /// Currencies payments from this one can pay out in.
+    #[serde(default)]
+    pub payouts: Vec<String>,
+}
+
+fn enabled_default() -> bool {
+    true
+}
+
+impl Currency {
+    /// `amount` written with this currency's minor units, e.g. "1500.00".
+    pub fn format(&self, amount: f64) -> String {
+        format!("{:.*}", self.minor_units as usize, amount)
+    }
+}
+
+#[derive(Deserialize)]
+struct CurrencyFile {
+    currencies: Vec<Currency>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Registry {
+    currencies: Vec<Currency>,
+    corridors: BTreeSet<(String, String)>,
+}
+
+impl Registry {
+    pub fn from_env() -> anyhow::Result<Self> {
+        let path = std::env::var("CURRENCIES_FILE").unwrap_or_else(|_| "config/currencies.json".to_string());
+        let raw = std::fs::read_to_string(&path).map_err(|e| anyhow::anyhow!("cannot read currencies file {}: {}", path, e))?;
+        let file: CurrencyFile = serde_json::from_str(&raw).map_err(|e| anyhow::anyhow!("invalid currencies file {}: {}", path, e))?;
+        let allowed = std::env::var("ALLOWED_CURRENCIES").ok().filter(|s| !s.trim().is_empty());
+        let corridors = std::env::var("CORRIDORS").ok().filter(|s| !s.trim().is_empty());
+        Self::build(file.currencies, allowed.as_deref(), corridors.as_deref())
+    }
+
+    pub(crate) fn build(currencies: Vec<Currency>, allowed: Option<&str>, corridors: Option<&str>) -> anyhow::Result<Self> {
+        let mut seen = HashSet::new();
+        for c in &currencies {
+            if !is_code(&c.code) {
+                anyhow::bail!("{:?} is not a currency code", c.code);
+            }
+            if !seen.insert(c.code.clone()) {
+                anyhow::bail!("{} is listed twice", c.code);
+            }
+            if c.minor_units > 3 {
+                anyhow::bail!("{}: minor_units must be 0 to 3", c.code);
+            }
+            if !(c.min_amount > 0.0 && c.min_amount <= c.max_amount) {
+                anyhow::bail!("{}: need 0 < min_amount <= max_amount", c.code);
+            }
+        }
+        let allowed: Option<Vec<String>> = allowed.map(|raw| raw.split(',').map(|c| c.trim().to_uppercase()).filter(|c| !c.is_empty()).collect());
+        if let Some(unknown) = allowed.iter().flatten().find(|c| !seen.contains(*c)) {
+            anyhow::bail!("ALLOWED_CURRENCIES: {} is not in the currencies file", unknown);
+        }
+
+        let pairs: Vec<(String, String)> = match corridors {
+            Some(raw) => parse_corridors(raw)?,
+            None => currencies.iter().flat_map(|c| c.payouts.iter().map(|d| (c.code.clone(), d.trim().to_uppercase()))).collect(),
+        };
+        if let Some((src, dest)) = pairs.iter().find(|(s, d)| !seen.contains(s) || !seen.contains(d)) {
+            anyhow::bail!("corridor {}>{} uses a currency that is not in the currencies file", src, dest);
+        }
+        let enabled: HashSet<&str> = currencies.iter().filter(|c| c.enabled).map(|c| c.code.as_str()).collect();
+        let corridors: BTreeSet<(String, String)> = pairs
+            .into_iter()
+            .filter(|(s, d)| enabled.contains(s.as_str()) && enabled.contains(d.as_str()))
+            .filter(|(s, _)| allowed.as_ref().is_none_or(|a| a.contains(s)))
+            .collect();
+        if corridors.is_empty() {
+            anyhow::bail!("no corridor is open between enabled currencies");
+        }
+        Ok(Registry { currencies, corridors })
+    }
+
+    /// A known currency, enabled or not.
+    pub fn get(&self, code: &str) -> Option<&Currency> {
+        self.currencies.iter().find(|c| c.code == code)
+    }
+
+    /// Currencies payers can pay from (enabled, with an open corridor), in
+    /// file order.
+    pub fn sources(&self) -> Vec<&Currency> {
+        self.currencies.iter().filter(|c| self.is_source(&c.code)).collect()
+    }
+
+    pub fn is_source(&self, code: &str) -> bool {
+        self.corridors.iter().any(|(s, _)| s == code)
+    }
+
+    pub fn allows(&self, source: &str, dest: &str) -> bool {
+        self.corridors.contains(&(source.to_string(), dest.to_string()))
+    }
+
+    /// Destination currencies with at least one corridor, INR first.
+    pub fn destinations(&self) -> Vec<&Currency> {
+        let mut out: Vec<&Currency> = self.currencies.iter().filter(|c| self.corridors.iter().any(|(_, d)| *d == c.code)).collect();
+        out.sort_by_key(|c| (c.code != "INR", c.code.clone()));
+        out
+    }
+
+    /// Source currencies that can pay out in `dest`.
+    pub fn sources_for(&self, dest: &str) -> Vec<String> {
+        self.corridors.iter().filter(|(_, d)| d == dest).map(|(s, _)| s.clone()).collect()
+    }
+
+    /// Destination currencies `source` can pay out in.
+    pub fn payouts_for(&self, source: &str) -> Vec<String> {
+        self.corridors.iter().filter(|(s, _)| s == source).map(|(_, d)| d.clone()).collect()
+    }
+
+    /// The full matrix as `{dest: [sources]}`.
+    pub fn matrix(&self) -> Vec<(String, Vec<String>)> {
+        self.destinations().into_iter().map(|d| (d.code.clone(), self.sources_for(&d.code))).collect()
+    }
+
+    /// Rounding for a quote from `source` to `dest`; unknown currencies use cents.
+    pub fn precision(&self, source: &str, dest: &str) -> Precision {
+        let minor = |code: &str| self.get(code).map(|c| c.minor_units).unwrap_or(2);
+        Precision { source: minor(source), dest: minor(dest) }
+    }
+
+    /// Whether one payment may send `amount` of `code`.
+    pub fn check_amount(&self, code: &str, amount: f64) -> Result<(), String> {
+        let c = self.get(code).filter(|c| self.is_source(&c.code)).ok_or_else(|| format!("{} is not a supported currency", code))?;
+        if amount < c.min_amount || amount > c.max_amount {
+            return Err(format!("Payments in {} must be between {} and {} {}", c.code, c.format(c.min_amount), c.format(c.max_amount), c.code));
+        }
+        Ok(())
+    }
+}
+
+fn is_code(code: &str) -> bool {
+    code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase())
+}
+
+fn parse_corridors(raw: &str) -> anyhow::Result<Vec<(String, String)>> {
+    let mut out = Vec::new();
+    for pair in raw.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (src, dest) = pair.split_once('>').ok_or_else(|| anyhow::anyhow!("CORRIDORS entry {:?} must look like AED>INR", pair))?;
+        let (src, dest) = (src.trim().to_uppercase(), dest.trim().to_uppercase());
+        for code in [&src, &dest] {
+            if !is_code(code) {
+                anyhow::bail!("CORRIDORS entry {:?}: {:?} is not a currency code", pair, code);
+            }
+        }
+        out.push((src, dest));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn currency(code: &str, enabled: bool, payouts: &[&str]) -> Currency {
+        Currency {
+            code: code.into(),
+            name: code.into(),
+            country: code.into(),
+            calendar: None,
+            flag: String::new(),
+            symbol: code.into(),
+            minor_units: 2,
+            enabled,
+            min_amount: 1.0,
+            max_amount: 50_000.0,
+            payouts: payouts.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    fn currencies() -> Vec<Currency> {
+        vec![
+            currency("INR", true, &["INR", "NPR"]),
+            currency("AED", true, &["INR", "NPR"]),
+            currency("NPR", true, &["INR"]),
+            currency("EUR", true, &["inr", "usd"]),
+            currency("USD", false, &["INR"]),
+        ]
+    }
+
+    fn pairs(registry: &Registry) -> Vec<(String, String)> {
+        registry.corridors.iter().cloned().collect()
+    }
+
+    fn pair(src: &str, dest: &str) -> (String, String) {
+        (src.into(), dest.into())
+    }
+
+    #[test]
+    fn corridors_come_from_the_payout_lists_between_enabled_currencies() {
+        let registry = Registry::build(currencies(), None, None).unwrap();
+        assert_eq!(
+            pairs(&registry),
+            [pair("AED", "INR"), pair("AED", "NPR"), pair("EUR", "INR"), pair("INR", "INR"), pair("INR", "NPR"), pair("NPR", "INR")]
+        );
+        // USD is known but disabled, on either side
+        assert!(registry.get("USD").is_some());
+        assert!(!registry.is_source("USD") && !registry.allows("EUR", "USD"));
+        assert_eq!(registry.destinations().iter().map(|c| c.code.as_str()).collect::<Vec<_>>(), ["INR", "NPR"]);
+        assert_eq!(registry.sources_for("NPR"), ["AED", "INR"]);
+    }
+
+    #[test]
+    fn allowed_currencies_narrow_the_sources_only() {
+        let registry = Registry::build(currencies(), Some(" aed, npr "), None).unwrap();
+        assert_eq!(pairs(&registry), [pair("AED", "INR"), pair("AED", "NPR"), pair("NPR", "INR")]);
+        assert_eq!(registry.sources().iter().map(|c| c.code.as_str()).collect::<Vec<_>>(), ["AED", "NPR"]);
+        assert!(Registry::build(currencies(), Some("AED,XYZ"), None).is_err());
+    }
+
+    #[test]
+    fn corridors_replace_the_payout_lists() {
+        let registry = Registry::build(currencies(), None, Some("aed>npr, EUR > INR, USD>INR")).unwrap();
+        assert_eq!(pairs(&registry), [pair("AED", "NPR"), pair("EUR", "INR")]);
+        assert!(!registry.allows("AED", "INR"));
+        assert!(Registry::build(currencies(), None, Some("AED-INR")).is_err());
+        assert!(Registry::build(currencies(), None, Some("AED>GBP")).is_err());
+    }
+
+    #[test]
+    fn nothing_open_is_an_error() {
+        assert!(Registry::build(currencies(), Some("USD"), None).is_err());
+        assert!(Registry::build(currencies(), None, Some("USD>INR")).is_err());
+    }
+
+    #[test]
+    fn bad_entries_are_rejected() {
+        let mut twice = currencies();
+        twice.push(currency("AED", true, &[]));
+        assert!(Registry::build(twice, None, None).is_err());
+        assert!(Registry::build(vec![currency("aed", true, &["INR"])], None, None).is_err());
+        let inverted = Currency { min_amount: 10.0, max_amount: 1.0, ..currency("AED", true, &["AED"]) };
+        assert!(Registry::build(vec![inverted], None, None).is_err());
+    }
+
+    #[test]
+    fn amounts_are_checked_against_the_source_currency() {
+        let registry = Registry::build(currencies(), None, None).unwrap();
+        assert!(registry.check_amount("AED", 1.0).is_ok());
+        assert!(registry.check_amount("AED", 50_000.01).is_err());
+        assert!(registry.check_amount("USD", 100.0).is_err());
+    }
+}
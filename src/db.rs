@@ -1,6 +1,10 @@
 use sqlx::{PgPool, postgres::PgPoolOptions};
 use uuid::Uuid;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::types::Json;
+
+use crate::ai::ReasonHit;
+use crate::limits;
 
 #[derive(Clone)]
 pub struct Db {
@@ -10,6 +14,8 @@ pub struct Db {
 #[derive(sqlx::FromRow, Debug, Clone)]
 pub struct Payment {
     pub id: Uuid,
+    /// The receiver's name as typed on the form (the column predates
+    /// `sender_name`).
     pub payer_name: String,
     pub upi_id: String,
     pub amount_inr: f64,
@@ -28,6 +34,192 @@ pub struct Payment {
     pub risk_score: Option<i32>,
     pub risk_label: Option<String>,
     pub risk_reasons: Option<String>,
+    pub risk_reason_codes: Json<Vec<ReasonHit>>,
+    pub payer_tz: Option<String>,
+    pub client_ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub device_fp: Option<String>,
+    pub ip_country: Option<String>,
+    pub payee_lookup: Option<String>,
+    pub payee_registered_name: Option<String>,
+    pub payee_name_similarity: Option<f64>,
+    pub rate_provider: Option<String>,
+    pub dest_currency: String,
+    pub dest_amount: f64,
+    pub rate_to_dest: Option<f64>,
+    pub rail: String,
+    pub settles_at: Option<DateTime<Utc>>,
+    pub expected_credit_date: Option<NaiveDate>,
+    /// The payer's own name; None on payments made before it was asked for.
+    pub sender_name: Option<String>,
+}
+
+/// Column values for a new payment row (status starts as `pending`).
+#[derive(Debug, Clone)]
+pub struct NewPayment<'a> {
+    /// The receiver's name.
+    pub payer_name: &'a str,
+    pub sender_name: Option<&'a str>,
+    pub upi_id: &'a str,
+    pub amount_inr: f64,
+    pub note: Option<&'a str>,
+    pub source_currency: &'a str,
+    pub source_amount: f64,
+    pub rate_to_inr: Option<f64>,
+    pub rate_timestamp: Option<DateTime<Utc>>,
+    pub rate_provider: Option<&'a str>,
+    pub fee_transfer_inr: f64,
+    pub fee_platform_inr: f64,
+    pub fee_src_total: f64,
+    pub total_inr: f64,
+    pub total_src: f64,
+    pub risk_score: i32,
+    pub risk_label: &'a str,
+    pub risk_reasons: Option<&'a str>,
+    pub risk_reason_codes: &'a [ReasonHit],
+    pub payer_tz: Option<&'a str>,
+    pub client_ip: Option<&'a str>,
+    pub user_agent: Option<&'a str>,
+    pub device_fp: Option<&'a str>,
+    pub ip_country: Option<&'a str>,
+    /// `verified`, `not_found` or `error`; None when no lookup ran.
+    pub payee_lookup: Option<&'a str>,
+    pub payee_registered_name: Option<&'a str>,
+    pub payee_name_similarity: Option<f64>,
+    pub dest_currency: &'a str,
+    pub dest_amount: f64,
+    pub rate_to_dest: Option<f64>,
+    /// Scheme chosen by the payee's rail: "upi", "imps" or "neft".
+    pub rail: &'a str,
+    pub settles_at: Option<DateTime<Utc>>,
+    /// Local date in the payout country.
+    pub expected_credit_date: Option<NaiveDate>,
+}
+
+/// Live vs shadow label pair with counts and score drift, for divergence reports.
+#[derive(sqlx::FromRow, Debug, Clone, serde::Serialize)]
+pub struct ShadowLabelPair {
+    pub live_label: String,
+    pub shadow_label: String,
+    pub payments: i64,
+    pub mean_score_delta: f64,
+    pub mean_abs_score_delta: f64,
+}
+
+#[derive(sqlx::FromRow, Debug, Clone, serde::Serialize)]
+pub struct ShadowDivergence {
+    pub payment_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub live_score: i32,
+    pub live_label: String,
+    pub shadow_score: i32,
+    pub shadow_label: String,
+}
+
+#[derive(sqlx::FromRow, Debug, Clone, serde::Serialize)]
+pub struct AssistantTurn {
+    pub id: i64,
+    pub session_id: Option<Uuid>,
+    pub payment_id: Option<Uuid>,
+    pub question: String,
+    pub resolved_question: String,
+    pub answer: String,
+    pub lang: String,
+    pub backend: String,
+    pub fallback: bool,
+    pub confidence: Option<f64>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewAssistantTurn<'a> {
+    pub session_id: Option<Uuid>,
+    pub payment_id: Option<Uuid>,
+    pub question: &'a str,
+    pub resolved_question: &'a str,
+    pub answer: &'a str,
+    pub lang: &'a str,
+    pub backend: &'a str,
+    pub fallback: bool,
+    pub confidence: Option<f64>,
+}
+
+/// A question that got the fallback answer, grouped by its normalized text.
+#[derive(sqlx::FromRow, Debug, Clone, serde::Serialize)]
+pub struct FallbackQuestion {
+    pub question: String,
+    pub lang: String,
+    pub asked: i64,
+    pub sessions: i64,
+    pub last_asked: DateTime<Utc>,
+}
+
+#[derive(sqlx::FromRow, Debug, Clone, serde::Serialize)]
+pub struct ReasonWeekCount {
+    pub week: DateTime<Utc>,
+    pub code: String,
+    pub hits: i64,
+}
+
+/// Open/high/low/close of the stored rates in one time bucket.
+#[derive(sqlx::FromRow, Debug, Clone, serde::Serialize)]
+pub struct FxCandle {
+    pub bucket: DateTime<Utc>,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub samples: i64,
+}
+
+#[derive(sqlx::FromRow, Debug, Clone, serde::Serialize)]
+pub struct RateAlert {
+    pub id: Uuid,
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub threshold: f64,
+    pub direction: String,
+    pub channel: String,
+    /// Never returned by the API: whoever holds an alert id or token
+    /// shouldn't learn where it is sent.
+    #[serde(skip)]
+    pub contact: String,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub triggered_at: Option<DateTime<Utc>>,
+    pub triggered_rate: Option<f64>,
+    pub triggered_rate_id: Option<Uuid>,
+    pub last_error: Option<String>,
+    /// When the contact opened the confirmation link; None while pending.
+    pub confirmed_at: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    pub confirm_token_hash: Option<String>,
+    #[serde(skip)]
+    pub manage_token_hash: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewRateAlert<'a> {
+    pub base_currency: &'a str,
+    pub quote_currency: &'a str,
+    pub threshold: f64,
+    pub direction: &'a str,
+    pub channel: &'a str,
+    pub contact: &'a str,
+    /// `alerts::token_hash` of the token sent to the contact; opening it
+    /// activates the alert.
+    pub confirm_token_hash: &'a str,
+    /// Hash of the token returned to the subscriber for GET and DELETE.
+    pub manage_token_hash: &'a str,
+}
+
+/// An active alert and the first recorded rate that crossed its threshold.
+#[derive(sqlx::FromRow, Debug, Clone)]
+pub struct AlertHit {
+    pub alert_id: Uuid,
+    pub rate_id: Uuid,
+    pub rate: f64,
+    pub fetched_at: DateTime<Utc>,
 }
 
 #[derive(sqlx::FromRow, Debug, Clone)]
@@ -36,6 +228,38 @@ pub struct Session {
     pub status: String,
     pub payment_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
+    pub client_ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub device_fp: Option<String>,
+}
+
+#[derive(sqlx::FromRow, Debug, Clone, serde::Serialize)]
+pub struct RiskLabel {
+    pub payment_id: Uuid,
+    pub label: String,
+    pub note: Option<String>,
+    pub labeled_by: Option<String>,
+    pub labeled_at: DateTime<Utc>,
+}
+
+/// A payment with its confirmed outcome (if any), for backtesting.
+#[derive(sqlx::FromRow, Debug, Clone)]
+pub struct LabeledPayment {
+    pub id: Uuid,
+    pub upi_id: String,
+    pub amount_inr: f64,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub source_currency: String,
+    pub risk_score: i32,
+    pub risk_label: String,
+    pub payer_tz: Option<String>,
+    pub device_payer_names: i64,
+    pub ip_country: Option<String>,
+    pub payee_lookup: Option<String>,
+    pub payee_registered_name: Option<String>,
+    pub payee_name_similarity: Option<f64>,
+    pub outcome: Option<String>,
 }
 
 impl Db {
@@ -55,54 +279,58 @@ impl Db {
         Ok(())
     }
 
-    pub async fn insert_payment(
-        &self,
-        payer_name: &str,
-        upi_id: &str,
-        amount_inr: f64,
-        note: Option<&str>,
-        source_currency: &str,
-        source_amount: f64,
-        rate_to_inr: Option<f64>,
-        rate_timestamp: Option<DateTime<Utc>>,
-        fee_transfer_inr: f64,
-        fee_platform_inr: f64,
-        fee_src_total: f64,
-        total_inr: f64,
-        total_src: f64,
-        risk_score: i32,
-        risk_label: &str,
-        risk_reasons: Option<&str>,
-    ) -> anyhow::Result<Uuid> {
+    pub async fn insert_payment(&self, p: &NewPayment<'_>) -> anyhow::Result<Uuid> {
         let id = Uuid::new_v4();
         sqlx::query(
             r#"INSERT INTO payments (
                     id, payer_name, upi_id, amount_inr, note, status,
                     source_currency, source_amount, rate_to_inr, rate_timestamp,
                     fee_transfer_inr, fee_platform_inr, fee_src_total, total_inr, total_src,
-                    risk_score, risk_label, risk_reasons
+                    risk_score, risk_label, risk_reasons, risk_reason_codes, payer_tz,
+                    client_ip, user_agent, device_fp, ip_country,
+                    payee_lookup, payee_registered_name, payee_name_similarity, rate_provider,
+                    dest_currency, dest_amount, rate_to_dest, rail, settles_at, expected_credit_date,
+                    sender_name
                ) VALUES (
                     $1,$2,$3,$4,$5,'pending',$6,$7,$8,$9,$10,$11,$12,$13,$14,
-                    $15,$16,$17
+                    $15,$16,$17,$18,$19,$20,$21,$22,$23,$24,$25,$26,$27,
+                    $28,$29,$30,$31,$32,$33,$34
                )"#,
         )
         .bind(id)
-        .bind(payer_name)
-        .bind(upi_id)
-        .bind(amount_inr)
-        .bind(note)
-        .bind(source_currency)
-        .bind(source_amount)
-        .bind(rate_to_inr)
-        .bind(rate_timestamp)
-        .bind(fee_transfer_inr)
-        .bind(fee_platform_inr)
-        .bind(fee_src_total)
-        .bind(total_inr)
-        .bind(total_src)
-        .bind(risk_score)
-        .bind(risk_label)
-        .bind(risk_reasons)
+        .bind(p.payer_name)
+        .bind(p.upi_id)
+        .bind(p.amount_inr)
+        .bind(p.note)
+        .bind(p.source_currency)
+        .bind(p.source_amount)
+        .bind(p.rate_to_inr)
+        .bind(p.rate_timestamp)
+        .bind(p.fee_transfer_inr)
+        .bind(p.fee_platform_inr)
+        .bind(p.fee_src_total)
+        .bind(p.total_inr)
+        .bind(p.total_src)
+        .bind(p.risk_score)
+        .bind(p.risk_label)
+        .bind(p.risk_reasons)
+        .bind(Json(p.risk_reason_codes))
+        .bind(p.payer_tz)
+        .bind(p.client_ip)
+        .bind(p.user_agent)
+        .bind(p.device_fp)
+        .bind(p.ip_country)
+        .bind(p.payee_lookup)
+        .bind(p.payee_registered_name)
+        .bind(p.payee_name_similarity)
+        .bind(p.rate_provider)
+        .bind(p.dest_currency)
+        .bind(p.dest_amount)
+        .bind(p.rate_to_dest)
+        .bind(p.rail)
+        .bind(p.settles_at)
+        .bind(p.expected_credit_date)
+        .bind(p.sender_name)
         .execute(&self.pool)
         .await?;
         Ok(id)
@@ -148,6 +376,182 @@ impl Db {
         Ok(id)
     }
 
+    /// The last `limit` recorded rates for a pair since `since`, newest first.
+    pub async fn recent_fx_rates(&self, base_currency: &str, quote_currency: &str, since: DateTime<Utc>, limit: i64) -> anyhow::Result<Vec<f64>> {
+        let rows: Vec<f64> = sqlx::query_scalar(
+            r#"SELECT rate FROM fx_rates
+                WHERE base_currency = $1 AND quote_currency = $2 AND fetched_at >= $3
+                ORDER BY fetched_at DESC
+                LIMIT $4"#,
+        )
+        .bind(base_currency)
+        .bind(quote_currency)
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// OHLC buckets of fetched rates in [from, to); `interval` is a
+    /// `date_trunc` unit ("hour", "day"), bucketed in UTC.
+    pub async fn fx_candles(
+        &self,
+        base_currency: &str,
+        quote_currency: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        interval: &str,
+    ) -> anyhow::Result<Vec<FxCandle>> {
+        let rows = sqlx::query_as::<_, FxCandle>(
+            r#"SELECT date_trunc($5, fetched_at, 'UTC') AS bucket,
+                      (array_agg(rate ORDER BY fetched_at ASC))[1] AS open,
+                      MAX(rate) AS high,
+                      MIN(rate) AS low,
+                      (array_agg(rate ORDER BY fetched_at DESC))[1] AS close,
+                      COUNT(*) AS samples
+                 FROM fx_rates
+                WHERE base_currency = $1 AND quote_currency = $2
+                  AND fetched_at >= $3 AND fetched_at < $4
+                GROUP BY 1
+                ORDER BY 1"#,
+        )
+        .bind(base_currency)
+        .bind(quote_currency)
+        .bind(from)
+        .bind(to)
+        .bind(interval)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Insert an alert awaiting confirmation (inactive).
+    pub async fn insert_rate_alert(&self, a: &NewRateAlert<'_>) -> anyhow::Result<RateAlert> {
+        let row = sqlx::query_as::<_, RateAlert>(
+            r#"INSERT INTO rate_alerts (id, base_currency, quote_currency, threshold, direction, channel, contact,
+                                        active, confirm_token_hash, manage_token_hash)
+               VALUES ($1,$2,$3,$4,$5,$6,$7, FALSE, $8, $9)
+               RETURNING *"#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(a.base_currency)
+        .bind(a.quote_currency)
+        .bind(a.threshold)
+        .bind(a.direction)
+        .bind(a.channel)
+        .bind(a.contact)
+        .bind(a.confirm_token_hash)
+        .bind(a.manage_token_hash)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Activate a pending alert created at or after `created_after`; None if
+    /// the token (by `alerts::token_hash`) is wrong, already used or expired.
+    pub async fn confirm_rate_alert(&self, id: Uuid, token_hash: &str, created_after: DateTime<Utc>) -> anyhow::Result<Option<RateAlert>> {
+        let row = sqlx::query_as::<_, RateAlert>(
+            r#"UPDATE rate_alerts
+                  SET active = TRUE, confirmed_at = now(), confirm_token_hash = NULL
+                WHERE id = $1 AND confirm_token_hash = $2
+                  AND created_at >= $3
+                RETURNING *"#,
+        )
+        .bind(id)
+        .bind(token_hash)
+        .bind(created_after)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// The alert the management token hashing to `manage_token_hash` was
+    /// issued for.
+    pub async fn get_rate_alert(&self, id: Uuid, manage_token_hash: &str) -> anyhow::Result<Option<RateAlert>> {
+        let row = sqlx::query_as::<_, RateAlert>("SELECT * FROM rate_alerts WHERE id = $1 AND manage_token_hash = $2")
+            .bind(id)
+            .bind(manage_token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Deactivate an alert, or withdraw one still awaiting confirmation,
+    /// without firing it; false if the token is wrong or it was neither.
+    pub async fn cancel_rate_alert(&self, id: Uuid, manage_token_hash: &str) -> anyhow::Result<bool> {
+        let res = sqlx::query(
+            r#"UPDATE rate_alerts SET active = FALSE, confirm_token_hash = NULL
+                WHERE id = $1 AND manage_token_hash = $2
+                  AND (active OR confirm_token_hash IS NOT NULL)"#,
+        )
+        .bind(id)
+        .bind(manage_token_hash)
+        .execute(&self.pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    /// Base currencies with active alerts, so their rates can be refreshed.
+    pub async fn active_alert_bases(&self) -> anyhow::Result<Vec<String>> {
+        let rows: Vec<String> = sqlx::query_scalar("SELECT DISTINCT base_currency FROM rate_alerts WHERE active ORDER BY 1")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows)
+    }
+
+    /// For each active alert, the earliest rate recorded after `since` (and
+    /// after the alert was confirmed) that crosses its threshold.
+    pub async fn rate_alert_hits(&self, since: Option<DateTime<Utc>>) -> anyhow::Result<Vec<AlertHit>> {
+        let rows = sqlx::query_as::<_, AlertHit>(
+            r#"SELECT DISTINCT ON (a.id) a.id AS alert_id, r.id AS rate_id, r.rate, r.fetched_at
+                 FROM rate_alerts a
+                 JOIN fx_rates r
+                   ON r.base_currency = a.base_currency
+                  AND r.quote_currency = a.quote_currency
+                  AND r.recorded_at >= COALESCE(a.confirmed_at, a.created_at)
+                  AND ($1::timestamptz IS NULL OR r.recorded_at > $1)
+                  AND ((a.direction = 'above' AND r.rate >= a.threshold)
+                    OR (a.direction = 'below' AND r.rate <= a.threshold))
+                WHERE a.active
+                ORDER BY a.id, r.recorded_at, r.id"#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Mark an alert fired; None if another run already claimed it.
+    pub async fn claim_rate_alert(&self, hit: &AlertHit) -> anyhow::Result<Option<RateAlert>> {
+        let row = sqlx::query_as::<_, RateAlert>(
+            r#"UPDATE rate_alerts
+                  SET active = FALSE, triggered_at = now(), triggered_rate = $2, triggered_rate_id = $3, last_error = NULL
+                WHERE id = $1 AND active
+                RETURNING *"#,
+        )
+        .bind(hit.alert_id)
+        .bind(hit.rate)
+        .bind(hit.rate_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Re-arm an alert whose notification could not be delivered.
+    pub async fn release_rate_alert(&self, id: Uuid, error: &str) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"UPDATE rate_alerts
+                  SET active = TRUE, triggered_at = NULL, triggered_rate = NULL, triggered_rate_id = NULL, last_error = $2
+                WHERE id = $1"#,
+        )
+        .bind(id)
+        .bind(error)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn create_session(&self) -> anyhow::Result<Uuid> {
         let id = Uuid::new_v4();
         sqlx::query("INSERT INTO sessions (id) VALUES ($1)")
@@ -175,6 +579,73 @@ impl Db {
         Ok(())
     }
 
+    pub async fn set_session_client(
+        &self,
+        id: Uuid,
+        client_ip: Option<&str>,
+        user_agent: Option<&str>,
+        device_fp: Option<&str>,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"UPDATE sessions
+                  SET client_ip = COALESCE($2, client_ip),
+                      user_agent = COALESCE($3, user_agent),
+                      device_fp = COALESCE($4, device_fp)
+                WHERE id = $1"#,
+        )
+        .bind(id)
+        .bind(client_ip)
+        .bind(user_agent)
+        .bind(device_fp)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// INR value of the receiver credits of a payer's payments since each of
+    /// `starts`.
+    pub async fn payer_credits_inr(&self, payer: limits::Payer<'_>, starts: [DateTime<Utc>; 3]) -> anyhow::Result<[f64; 3]> {
+        let earliest = starts.iter().min().copied().unwrap_or_else(Utc::now);
+        let (column, key) = match payer {
+            limits::Payer::Device(fp) => ("device_fp", fp),
+            limits::Payer::Ip(ip) => ("client_ip", ip),
+        };
+        let sql = format!(
+            r#"SELECT COALESCE(SUM(amount_inr) FILTER (WHERE created_at >= $2), 0),
+                      COALESCE(SUM(amount_inr) FILTER (WHERE created_at >= $3), 0),
+                      COALESCE(SUM(amount_inr) FILTER (WHERE created_at >= $4), 0)
+                 FROM payments
+                WHERE {} = $1 AND created_at >= $5"#,
+            column
+        );
+        let (a, b, c): (f64, f64, f64) = sqlx::query_as(&sql)
+            .bind(key)
+            .bind(starts[0])
+            .bind(starts[1])
+            .bind(starts[2])
+            .bind(earliest)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok([a, b, c])
+    }
+
+    /// Distinct payer (sender) names seen from a device since `since`,
+    /// counting `sender_name` itself.
+    pub async fn device_payer_names(&self, device_fp: &str, sender_name: &str, since: DateTime<Utc>) -> anyhow::Result<i64> {
+        let others: i64 = sqlx::query_scalar(
+            r#"SELECT count(DISTINCT lower(trim(sender_name)))
+                 FROM payments
+                WHERE device_fp = $1 AND created_at >= $2
+                  AND lower(trim(sender_name)) <> lower(trim($3))"#,
+        )
+        .bind(device_fp)
+        .bind(since)
+        .bind(sender_name)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(others + 1)
+    }
+
     pub async fn get_session(&self, id: Uuid) -> anyhow::Result<Option<Session>> {
         let rec = sqlx::query_as::<_, Session>("SELECT * FROM sessions WHERE id = $1")
             .bind(id)
@@ -182,4 +653,239 @@ impl Db {
             .await?;
         Ok(rec)
     }
+
+    pub async fn upsert_risk_label(
+        &self,
+        payment_id: Uuid,
+        label: &str,
+        note: Option<&str>,
+        labeled_by: Option<&str>,
+    ) -> anyhow::Result<RiskLabel> {
+        let rec = sqlx::query_as::<_, RiskLabel>(
+            r#"INSERT INTO risk_labels (payment_id, label, note, labeled_by)
+                VALUES ($1,$2,$3,$4)
+               ON CONFLICT (payment_id) DO UPDATE
+                SET label = EXCLUDED.label, note = EXCLUDED.note,
+                    labeled_by = EXCLUDED.labeled_by, labeled_at = now()
+               RETURNING *"#,
+        )
+        .bind(payment_id)
+        .bind(label)
+        .bind(note)
+        .bind(labeled_by)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(rec)
+    }
+
+    pub async fn get_risk_label(&self, payment_id: Uuid) -> anyhow::Result<Option<RiskLabel>> {
+        let rec = sqlx::query_as::<_, RiskLabel>("SELECT * FROM risk_labels WHERE payment_id = $1")
+            .bind(payment_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(rec)
+    }
+
+    pub async fn list_labeled_payments(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<Vec<LabeledPayment>> {
+        let rows = sqlx::query_as::<_, LabeledPayment>(
+            r#"SELECT p.id, p.upi_id, p.amount_inr, p.note, p.created_at, p.source_currency,
+                      p.risk_score, p.risk_label, p.payer_tz, p.ip_country,
+                      p.payee_lookup, p.payee_registered_name, p.payee_name_similarity,
+                      l.label AS outcome,
+                      COALESCE(d.names, 0) AS device_payer_names
+                 FROM payments p
+                 LEFT JOIN risk_labels l ON l.payment_id = p.id
+                 LEFT JOIN LATERAL (
+                      SELECT count(DISTINCT lower(trim(o.sender_name))) AS names
+                        FROM payments o
+                       WHERE o.device_fp = p.device_fp
+                         AND o.created_at <= p.created_at
+                         AND o.created_at > p.created_at - interval '24 hours'
+                 ) d ON p.device_fp IS NOT NULL AND p.sender_name IS NOT NULL
+                WHERE ($1::timestamptz IS NULL OR p.created_at >= $1)
+                  AND ($2::timestamptz IS NULL OR p.created_at < $2)
+                ORDER BY p.created_at"#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Weekly hit counts per risk reason code over the last `weeks` weeks.
+    pub async fn risk_reason_weekly_counts(&self, weeks: i32, code: Option<&str>) -> anyhow::Result<Vec<ReasonWeekCount>> {
+        let rows = sqlx::query_as::<_, ReasonWeekCount>(
+            r#"SELECT date_trunc('week', p.created_at) AS week,
+                      r->>'code' AS code,
+                      count(*) AS hits
+                 FROM payments p
+                 CROSS JOIN LATERAL jsonb_array_elements(p.risk_reason_codes) r
+                WHERE p.created_at >= date_trunc('week', now()) - make_interval(weeks => $1 - 1)
+                  AND ($2::text IS NULL OR r->>'code' = $2)
+                GROUP BY 1, 2
+                ORDER BY 1 DESC, 3 DESC"#,
+        )
+        .bind(weeks)
+        .bind(code)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn insert_risk_shadow(
+        &self,
+        payment_id: Uuid,
+        rules_version: &str,
+        score: i32,
+        label: &str,
+        reason_codes: &[ReasonHit],
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"INSERT INTO risk_shadow (payment_id, rules_version, score, label, reason_codes)
+                VALUES ($1,$2,$3,$4,$5)
+               ON CONFLICT (payment_id, rules_version) DO NOTHING"#,
+        )
+        .bind(payment_id)
+        .bind(rules_version)
+        .bind(score)
+        .bind(label)
+        .bind(Json(reason_codes))
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn shadow_label_pairs(
+        &self,
+        rules_version: Option<&str>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<Vec<ShadowLabelPair>> {
+        let rows = sqlx::query_as::<_, ShadowLabelPair>(
+            r#"SELECT p.risk_label AS live_label,
+                      s.label AS shadow_label,
+                      count(*) AS payments,
+                      avg(s.score - p.risk_score)::float8 AS mean_score_delta,
+                      avg(abs(s.score - p.risk_score))::float8 AS mean_abs_score_delta
+                 FROM risk_shadow s
+                 JOIN payments p ON p.id = s.payment_id
+                WHERE ($1::text IS NULL OR s.rules_version = $1)
+                  AND ($2::timestamptz IS NULL OR p.created_at >= $2)
+                  AND ($3::timestamptz IS NULL OR p.created_at < $3)
+                GROUP BY 1, 2
+                ORDER BY 1, 2"#,
+        )
+        .bind(rules_version)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn shadow_divergences(
+        &self,
+        rules_version: Option<&str>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        limit: i64,
+    ) -> anyhow::Result<Vec<ShadowDivergence>> {
+        let rows = sqlx::query_as::<_, ShadowDivergence>(
+            r#"SELECT p.id AS payment_id, p.created_at,
+                      p.risk_score AS live_score, p.risk_label AS live_label,
+                      s.score AS shadow_score, s.label AS shadow_label
+                 FROM risk_shadow s
+                 JOIN payments p ON p.id = s.payment_id
+                WHERE s.label <> p.risk_label
+                  AND ($1::text IS NULL OR s.rules_version = $1)
+                  AND ($2::timestamptz IS NULL OR p.created_at >= $2)
+                  AND ($3::timestamptz IS NULL OR p.created_at < $3)
+                ORDER BY p.created_at DESC
+                LIMIT $4"#,
+        )
+        .bind(rules_version)
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn insert_assistant_turn(&self, t: &NewAssistantTurn<'_>) -> anyhow::Result<i64> {
+        let id: i64 = sqlx::query_scalar(
+            r#"INSERT INTO assistant_turns
+                  (session_id, payment_id, question, resolved_question, answer, lang, backend, fallback, confidence)
+               VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9)
+               RETURNING id"#,
+        )
+        .bind(t.session_id)
+        .bind(t.payment_id)
+        .bind(t.question)
+        .bind(t.resolved_question)
+        .bind(t.answer)
+        .bind(t.lang)
+        .bind(t.backend)
+        .bind(t.fallback)
+        .bind(t.confidence)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(id)
+    }
+
+    /// The session's turns, oldest first.
+    pub async fn assistant_turns(&self, session_id: Uuid) -> anyhow::Result<Vec<AssistantTurn>> {
+        let rows = sqlx::query_as::<_, AssistantTurn>(
+            "SELECT * FROM assistant_turns WHERE session_id = $1 ORDER BY created_at, id",
+        )
+        .bind(session_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn last_assistant_turn(&self, session_id: Uuid) -> anyhow::Result<Option<AssistantTurn>> {
+        let rec = sqlx::query_as::<_, AssistantTurn>(
+            "SELECT * FROM assistant_turns WHERE session_id = $1 ORDER BY created_at DESC, id DESC LIMIT 1",
+        )
+        .bind(session_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(rec)
+    }
+
+    pub async fn assistant_fallbacks(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        lang: Option<&str>,
+        limit: i64,
+    ) -> anyhow::Result<Vec<FallbackQuestion>> {
+        let rows = sqlx::query_as::<_, FallbackQuestion>(
+            r#"SELECT lower(btrim(resolved_question)) AS question, lang,
+                      COUNT(*) AS asked,
+                      COUNT(DISTINCT session_id) AS sessions,
+                      MAX(created_at) AS last_asked
+                 FROM assistant_turns
+                WHERE fallback
+                  AND ($1::timestamptz IS NULL OR created_at >= $1)
+                  AND ($2::timestamptz IS NULL OR created_at < $2)
+                  AND ($3::text IS NULL OR lang = $3)
+                GROUP BY 1, 2
+                ORDER BY asked DESC, last_asked DESC
+                LIMIT $4"#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(lang)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
 }
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{Html, IntoResponse, Response};
+use serde::Serialize;
+use tera::Context;
+
+use crate::fx::RateError;
+use crate::limits::LimitError;
+use crate::rails::RailError;
+use crate::validation::FieldError;
+use crate::AppState;
+
+// Errors from page and API handlers. An AppError turns into a response that carries
+// its Problem as an extension; the `render` middleware then writes it out by
+// the request's Accept header: an RFC 7807 application/problem+json body for
+// API clients (and for /api/ routes unless the client asks for HTML), or HTML
+// for browsers. HTML errors that carry the payer form
+// re-show it with what was typed and each field's error; others use
+// error.html.
+
+#[derive(Debug)]
+pub enum ErrorKind {
+    /// Form fields failed validation.
+    Invalid(Vec<FieldError>),
+    /// A malformed or unsupported request; the message says what to change.
+    BadRequest(String),
+    /// Unknown, or not the caller's to see.
+    NotFound(&'static str),
+    /// The caller used up its request budget.
+    Throttled(&'static str),
+    /// A message to a third party (e.g. an alert confirmation) couldn't be
+    /// sent. Logged in full.
+    Delivery(anyhow::Error),
+    Limit(LimitError),
+    Rate(RateError),
+    /// The payee's rail can't carry this payout.
+    Rail(RailError),
+    /// Logged in full; the client only sees a generic message.
+    Internal(anyhow::Error),
+}
+
+#[derive(Debug)]
+pub struct AppError {
+    pub kind: ErrorKind,
+    /// What the payer typed, to refill the payer form.
+    pub form: Option<serde_json::Value>,
+    pub sid: Option<String>,
+}
+
+impl AppError {
+    pub fn invalid(errors: Vec<FieldError>) -> Self {
+        ErrorKind::Invalid(errors).into()
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        ErrorKind::BadRequest(message.into()).into()
+    }
+
+    pub fn not_found(what: &'static str) -> Self {
+        ErrorKind::NotFound(what).into()
+    }
+
+    /// Re-show the payer form, refilled with `values`, for HTML clients.
+    pub fn on_form(mut self, values: serde_json::Value, sid: Option<&String>) -> Self {
+        self.form = Some(values);
+        self.sid = sid.cloned();
+        self
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match &self.kind {
+            ErrorKind::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorKind::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ErrorKind::NotFound(_) => StatusCode::NOT_FOUND,
+            ErrorKind::Throttled(_) => StatusCode::TOO_MANY_REQUESTS,
+            ErrorKind::Delivery(_) => StatusCode::BAD_GATEWAY,
+            ErrorKind::Limit(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorKind::Rate(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorKind::Rail(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorKind::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match &self.kind {
+            ErrorKind::Invalid(_) => "validation_failed",
+            ErrorKind::BadRequest(_) => "bad_request",
+            ErrorKind::NotFound(_) => "not_found",
+            ErrorKind::Throttled(_) => "too_many_requests",
+            ErrorKind::Delivery(_) => "delivery_failed",
+            ErrorKind::Limit(e) => e.code(),
+            ErrorKind::Rate(e) => e.code(),
+            ErrorKind::Rail(e) => e.code(),
+            ErrorKind::Internal(_) => "internal_error",
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match &self.kind {
+            ErrorKind::Invalid(_) => "Some payment details need fixing",
+            ErrorKind::BadRequest(_) => "Invalid request",
+            ErrorKind::NotFound(_) => "Not found",
+            ErrorKind::Throttled(_) => "Too many requests",
+            ErrorKind::Delivery(_) => "Message not sent",
+            ErrorKind::Limit(_) => "Payment limit reached",
+            ErrorKind::Rate(_) => "Exchange rate unavailable",
+            ErrorKind::Rail(_) => "Payout not available",
+            ErrorKind::Internal(_) => "Something went wrong",
+        }
+    }
+
+    fn detail(&self) -> String {
+        match &self.kind {
+            ErrorKind::Invalid(errors) if errors.len() == 1 => errors[0].message.clone(),
+            ErrorKind::Invalid(_) => "Check the highlighted fields.".to_string(),
+            ErrorKind::BadRequest(message) => message.clone(),
+            ErrorKind::NotFound(what) => format!("No {} with that id.", what),
+            ErrorKind::Throttled(what) => format!("Too many {}. Please try again later.", what),
+            ErrorKind::Delivery(_) => "We couldn't send the message. Please try again later.".to_string(),
+            ErrorKind::Limit(e) => e.to_string(),
+            ErrorKind::Rate(e) => e.to_string(),
+            ErrorKind::Rail(e) => e.to_string(),
+            ErrorKind::Internal(_) => "Something went wrong on our side. Please try again shortly.".to_string(),
+        }
+    }
+}
+
+impl From<ErrorKind> for AppError {
+    fn from(kind: ErrorKind) -> Self {
+        AppError { kind, form: None, sid: None }
+    }
+}
+
+impl From<LimitError> for AppError {
+    fn from(e: LimitError) -> Self {
+        ErrorKind::Limit(e).into()
+    }
+}
+
+impl From<RateError> for AppError {
+    fn from(e: RateError) -> Self {
+        ErrorKind::Rate(e).into()
+    }
+}
+
+impl From<RailError> for AppError {
+    fn from(e: RailError) -> Self {
+        ErrorKind::Rail(e).into()
+    }
+}
+
+impl From<anyhow::Error> for AppError {
+    fn from(e: anyhow::Error) -> Self {
+        ErrorKind::Internal(e).into()
+    }
+}
+
+/// An error as RFC 7807 problem details, plus what the HTML page needs.
+#[derive(Debug, Clone, Serialize)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub title: &'static str,
+    pub status: u16,
+    pub detail: String,
+    pub code: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+    #[serde(skip)]
+    pub form: Option<serde_json::Value>,
+    #[serde(skip)]
+    pub sid: Option<String>,
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        match &self.kind {
+            ErrorKind::Internal(e) => tracing::error!(error = %e, "request failed"),
+            ErrorKind::Delivery(e) => tracing::warn!(error = %e, "message not delivered"),
+            _ => {}
+        }
+        let status = self.status();
+        let problem = Problem {
+            kind: "about:blank",
+            title: self.title(),
+            status: status.as_u16(),
+            detail: self.detail(),
+            code: self.code(),
+            form: self.form,
+            sid: self.sid,
+            errors: match self.kind {
+                ErrorKind::Invalid(errors) => errors,
+                _ => Vec::new(),
+            },
+        };
+        // Plain-text fallback for routes without the `render` layer
+        let mut res = (status, problem.detail.clone()).into_response();
+        res.extensions_mut().insert(problem);
+        res
+    }
+}
+
+/// Whether the client asked for JSON rather than a page. API routes answer
+/// in JSON unless asked for HTML, e.g. by a browser following a link.
+fn wants_json(headers: &HeaderMap, path: &str) -> bool {
+    let accept = headers.get(header::ACCEPT).and_then(|v| v.to_str().ok()).unwrap_or("");
+    !accept.contains("text/html") && (accept.contains("json") || path.starts_with("/api/"))
+}
+
+/// Write out AppError responses as problem+json or HTML.
+pub async fn render(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    let json = wants_json(req.headers(), req.uri().path());
+    let mut res = next.run(req).await;
+    let Some(problem) = res.extensions_mut().remove::<Problem>() else { return res };
+    let status = res.status();
+    if json {
+        let body = serde_json::to_vec(&problem).unwrap_or_default();
+        return (status, [(header::CONTENT_TYPE, HeaderValue::from_static("application/problem+json"))], body).into_response();
+    }
+    let mut ctx = Context::new();
+    ctx.insert("error", &problem.detail);
+    ctx.insert("error_code", problem.code);
+    ctx.insert("error_title", problem.title);
+    let page = match &problem.form {
+        Some(form) => {
+            let fields: HashMap<&str, &str> = problem.errors.iter().map(|e| (e.field, e.message.as_str())).collect();
+            ctx.insert("field_errors", &fields);
+            ctx.insert("form", form);
+            if let Some(sid) = &problem.sid { ctx.insert("sid", sid); }
+            crate::routes::insert_currencies(&state, &mut ctx);
+            "pay_form.html"
+        }
+        None => {
+            ctx.insert("status", &problem.status);
+            "error.html"
+        }
+    };
+    let body = state.templates.render(page, &ctx).unwrap_or_else(|e| format!("Template error: {}", e));
+    (status, Html(body)).into_response()
+}
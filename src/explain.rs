@@ -0,0 +1,216 @@
+use crate::db::Payment;
+use crate::kb;
+
+// Templated answers about one specific payment, built only from what was
+// stored on it (fees, rate, rate_timestamp, provider, risk reasons). Callers
+// must have checked the asker owns the payment. Answers are English only, as
+// are the topic words; a question in another language that uses one (e.g.
+// "मेरी fees क्यों?") still gets the English answer.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Topic {
+    Fees,
+    Rate,
+    Risk,
+    Status,
+}
+
+const FEE_WORDS: &[&str] = &["fee", "fees", "extra", "charge", "charged", "charges", "more", "total", "debited", "cost", "deducted"];
+const RATE_WORDS: &[&str] = &["rate", "fx", "exchange", "conversion", "converted", "inr", "received", "receive", "got"];
+const RISK_WORDS: &[&str] = &["risk", "risky", "fraud", "score", "medium", "high", "flagged", "suspicious", "safe"];
+const STATUS_WORDS: &[&str] = &["status", "done", "complete", "completed", "pending", "success", "successful", "went"];
+
+/// Topics the question asks about, most specific first.
+pub fn topics(question: &str) -> Vec<Topic> {
+    let tokens = kb::tokenize(question);
+    let has = |words: &[&str]| tokens.iter().any(|t| words.contains(&t.as_str()));
+    let mut out = Vec::new();
+    if has(RISK_WORDS) { out.push(Topic::Risk); }
+    if has(FEE_WORDS) { out.push(Topic::Fees); }
+    if has(RATE_WORDS) { out.push(Topic::Rate); }
+    if has(STATUS_WORDS) { out.push(Topic::Status); }
+    out
+}
+
+fn money(v: f64) -> String {
+    format!("{:.2}", v)
+}
+
+pub fn fees(p: &Payment) -> String {
+    let fee_inr = p.fee_transfer_inr + p.fee_platform_inr;
+    if fee_inr <= 0.0 {
+        return format!(
+            "Your payment of {} had no fees: same-currency payments are free, so the receiver got the full amount.",
+            payout(p)
+        );
+    }
+    let received = if p.dest_currency == "INR" { payout(p) } else { format!("{} (₹{})", payout(p), money(p.amount_inr)) };
+    format!(
+        "You were debited {} {} (₹{}). The receiver got {}; the other ₹{} is fees: a ₹{} transfer fee and a ₹{} platform fee, which came to {} {} at your payment's rate.",
+        money(p.total_src), p.source_currency, money(p.total_inr),
+        received, money(fee_inr),
+        money(p.fee_transfer_inr), money(p.fee_platform_inr),
+        money(p.fee_src_total), p.source_currency
+    )
+}
+
+/// What the receiver got, e.g. "₹2260.04" or "3600.00 NPR".
+fn payout(p: &Payment) -> String {
+    if p.dest_currency == "INR" {
+        format!("₹{}", money(p.dest_amount))
+    } else {
+        format!("{} {}", money(p.dest_amount), p.dest_currency)
+    }
+}
+
+pub fn rate(p: &Payment) -> String {
+    if p.source_currency == p.dest_currency {
+        return format!("Your payment was in {}, so no currency conversion was needed.", p.dest_currency);
+    }
+    let rate = match p.rate_to_dest {
+        Some(r) => r,
+        None => return "No exchange rate was recorded for this payment.".to_string(),
+    };
+    let provider = p.rate_provider.as_deref().unwrap_or("unknown source");
+    let when = match p.rate_timestamp {
+        Some(ts) => format!("quoted at {}", ts.format("%Y-%m-%d %H:%M UTC")),
+        None => "with no live timestamp, because the live rate service was unavailable".to_string(),
+    };
+    format!(
+        "Your {} {} was converted at {:.4} {} per {} ({}, {}), giving the receiver {}.",
+        money(p.source_amount), p.source_currency, rate, p.dest_currency, p.source_currency, provider, when, payout(p)
+    )
+}
+
+pub fn risk(p: &Payment) -> String {
+    let label = p.risk_label.as_deref().unwrap_or("low");
+    let score = p.risk_score.unwrap_or(0);
+    if p.risk_reason_codes.is_empty() {
+        return format!("Your payment was rated {} risk ({}/100). No specific risk signals were found.", label, score);
+    }
+    let reasons = p
+        .risk_reason_codes
+        .iter()
+        .map(|h| format!("{} (+{})", h.code.text("en"), h.points))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "Your payment was rated {} risk ({}/100) because of: {}. This does not block the payment; it highlights what to double-check.",
+        label, score, reasons
+    )
+}
+
+pub fn status(p: &Payment) -> String {
+    match p.status.as_str() {
+        "success" => format!("Your payment of {} to {} completed successfully.", payout(p), p.payer_name),
+        other => format!("Your payment of {} is currently {}.", payout(p), other),
+    }
+}
+
+/// Answer `question` about `p`, or None when it isn't about this payment.
+pub fn explain(question: &str, p: &Payment) -> Option<String> {
+    let parts: Vec<String> = topics(question)
+        .into_iter()
+        .map(|t| match t {
+            Topic::Fees => fees(p),
+            Topic::Rate => rate(p),
+            Topic::Risk => risk(p),
+            Topic::Status => status(p),
+        })
+        .collect();
+    if parts.is_empty() { None } else { Some(parts.join(" ")) }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::types::Json;
+
+    use super::*;
+    use crate::ai::{ReasonHit, RiskReason};
+
+    /// 100 AED paid out as ₹2260.04, ₹124 in fees.
+    fn payment() -> Payment {
+        Payment {
+            id: uuid::Uuid::nil(),
+            payer_name: "Priya Sharma".into(),
+            upi_id: "priya.sharma@oksbi".into(),
+            amount_inr: 2260.04,
+            note: None,
+            status: "success".into(),
+            created_at: "2026-10-19T06:00:00Z".parse().unwrap(),
+            source_currency: "AED".into(),
+            source_amount: 100.0,
+            rate_to_inr: Some(22.6004),
+            rate_timestamp: Some("2026-10-19T05:59:30Z".parse().unwrap()),
+            fee_transfer_inr: 99.0,
+            fee_platform_inr: 25.0,
+            fee_src_total: 5.49,
+            total_inr: 2384.04,
+            total_src: 105.49,
+            risk_score: Some(35),
+            risk_label: Some("medium".into()),
+            risk_reasons: None,
+            risk_reason_codes: Json(vec![ReasonHit { code: RiskReason::CrossBorder, points: 10.0 }]),
+            payer_tz: None,
+            client_ip: None,
+            user_agent: None,
+            device_fp: None,
+            ip_country: None,
+            payee_lookup: None,
+            payee_registered_name: None,
+            payee_name_similarity: None,
+            rate_provider: Some("exchangerate.host-live".into()),
+            dest_currency: "INR".into(),
+            dest_amount: 2260.04,
+            rate_to_dest: Some(22.6004),
+            rail: "upi".into(),
+            settles_at: None,
+            expected_credit_date: None,
+            sender_name: None,
+        }
+    }
+
+    #[test]
+    fn topics_come_from_the_question_words() {
+        assert_eq!(topics("Why did I pay ₹124 extra?"), [Topic::Fees]);
+        assert_eq!(topics("Is it risky, and what rate did I get?"), [Topic::Risk, Topic::Rate]);
+        assert_eq!(topics("How do I add a beneficiary?"), []);
+        // English topic words work inside questions in other scripts
+        assert_eq!(topics("मेरी fees क्यों?"), [Topic::Fees]);
+    }
+
+    #[test]
+    fn fees_are_broken_down_from_the_stored_payment() {
+        let answer = fees(&payment());
+        assert!(answer.starts_with("You were debited 105.49 AED (₹2384.04). The receiver got ₹2260.04; the other ₹124.00 is fees"));
+        assert!(answer.contains("which came to 5.49 AED"));
+        let free = Payment { source_currency: "INR".into(), fee_transfer_inr: 0.0, fee_platform_inr: 0.0, ..payment() };
+        assert!(fees(&free).contains("had no fees"));
+    }
+
+    #[test]
+    fn rates_name_their_source_and_time() {
+        assert_eq!(
+            rate(&payment()),
+            "Your 100.00 AED was converted at 22.6004 INR per AED (exchangerate.host-live, quoted at 2026-10-19 05:59 UTC), giving the receiver ₹2260.04."
+        );
+        let npr = Payment { dest_currency: "NPR".into(), dest_amount: 3616.07, rate_to_dest: Some(36.1607), ..payment() };
+        assert!(rate(&npr).contains("36.1607 NPR per AED") && rate(&npr).ends_with("giving the receiver 3616.07 NPR."));
+        assert!(rate(&Payment { rate_to_dest: None, ..payment() }).starts_with("No exchange rate"));
+    }
+
+    #[test]
+    fn risk_lists_the_reasons_in_english() {
+        assert_eq!(
+            risk(&payment()),
+            "Your payment was rated medium risk (35/100) because of: cross-border remittance (+10). This does not block the payment; it highlights what to double-check."
+        );
+    }
+
+    #[test]
+    fn questions_off_the_payment_get_no_answer() {
+        assert_eq!(explain("How do I add a beneficiary?", &payment()), None);
+        let answer = explain("Is my payment complete, and what were the fees?", &payment()).unwrap();
+        assert!(answer.starts_with("You were debited") && answer.ends_with("completed successfully."));
+    }
+}
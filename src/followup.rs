@@ -0,0 +1,106 @@
+use crate::kb;
+
+// Resolve elliptical follow-ups against the session's previous question:
+// after "what are the fees from USD?", "and for EUR?" becomes "what are the
+// fees from EUR?". Only questions that carry nothing but a currency and/or an
+// amount (plus filler like "and", "what about") are treated as follow-ups;
+// anything with its own topic words stands alone.
+
+const CURRENCY_CODES: &[&str] = &["inr", "aed", "npr", "btn", "sgd", "mur", "eur", "lkr", "usd", "gbp"];
+
+// Filler around a follow-up, already in `kb::tokenize` form.
+const FILLER: &[&str] = &[
+    "and", "what", "about", "how", "also", "for", "in", "with", "then", "same", "if", "instead", "from", "to", "the", "is", "it",
+    "और", "के", "लिए", "में", "तो", "क्या", "अनि", "र", "को", "लागि", "मा", "नि", "सहित", "සහ", "සඳහා", "ද", "و", "ماذا", "عن", "في",
+];
+
+fn is_amount(token: &str) -> bool {
+    !token.is_empty() && token.chars().all(|c| c.is_ascii_digit())
+}
+
+/// The standalone question `question` stands for, if it is a follow-up to
+/// `previous`.
+pub fn resolve(question: &str, previous: Option<&str>) -> Option<String> {
+    let previous = previous?;
+    let tokens = kb::tokenize(question);
+    let content: Vec<&String> = tokens.iter().filter(|t| !FILLER.contains(&t.as_str())).collect();
+    if content.is_empty() || content.len() > 3 {
+        return None;
+    }
+    if !content.iter().all(|t| CURRENCY_CODES.contains(&t.as_str()) || is_amount(t)) {
+        return None;
+    }
+    let mut resolved = previous.to_string();
+    let mut leftover = Vec::new();
+    for t in content {
+        let kind = |w: &str| if is_amount(w) { 0 } else if CURRENCY_CODES.contains(&w) { 1 } else { 2 };
+        let old = kb::tokenize(&resolved).into_iter().find(|w| kind(w) == kind(t));
+        let replaced = old.map(|old| replace_word(&resolved, &old, &t.to_uppercase()));
+        match replaced {
+            Some(r) if r != resolved => resolved = r,
+            _ => leftover.push(t.to_uppercase()),
+        }
+    }
+    if !leftover.is_empty() {
+        let trimmed = resolved.trim_end_matches(['?', '؟', '।', '.']).to_string();
+        resolved = format!("{} ({})?", trimmed, leftover.join(" "));
+    }
+    Some(resolved)
+}
+
+/// Replace whole-word, case-insensitive occurrences of `old` (ASCII) in `text`.
+fn replace_word(text: &str, old: &str, new: &str) -> String {
+    let lower = text.to_ascii_lowercase();
+    let bytes = text.as_bytes();
+    let is_word = |i: usize| bytes.get(i).is_some_and(|b| b.is_ascii_alphanumeric());
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while let Some(pos) = lower[i..].find(old) {
+        let start = i + pos;
+        let end = start + old.len();
+        let bounded = (start == 0 || !is_word(start - 1)) && !is_word(end);
+        out.push_str(&text[i..start]);
+        out.push_str(if bounded { new } else { &text[start..end] });
+        i = end;
+    }
+    out.push_str(&text[i..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_new_currency_or_amount_replaces_the_old_one() {
+        let previous = Some("What are the fees from USD?");
+        assert_eq!(resolve("and for EUR?", previous).as_deref(), Some("What are the fees from EUR?"));
+        assert_eq!(resolve("what about aed", previous).as_deref(), Some("What are the fees from AED?"));
+        let previous = Some("How much INR do I get for 100 AED?");
+        assert_eq!(resolve("and 250?", previous).as_deref(), Some("How much INR do I get for 250 AED?"));
+    }
+
+    #[test]
+    fn what_has_no_counterpart_is_appended() {
+        assert_eq!(resolve("and for EUR?", Some("What are the fees?")).as_deref(), Some("What are the fees (EUR)?"));
+        assert_eq!(resolve("and 500 EUR", Some("What are the fees?")).as_deref(), Some("What are the fees (500 EUR)?"));
+    }
+
+    #[test]
+    fn follow_ups_in_other_languages() {
+        assert_eq!(resolve("और EUR के लिए?", Some("USD से शुल्क कितना है?")).as_deref(), Some("EUR से शुल्क कितना है?"));
+        assert_eq!(resolve("و EUR؟", Some("ما هي رسوم USD؟")).as_deref(), Some("ما هي رسوم EUR؟"));
+    }
+
+    #[test]
+    fn questions_with_their_own_topic_stand_alone() {
+        assert_eq!(resolve("what is the exchange rate for EUR?", Some("What are the fees from USD?")), None);
+        assert_eq!(resolve("and for EUR?", None), None);
+        assert_eq!(resolve("and?", Some("What are the fees from USD?")), None);
+    }
+
+    #[test]
+    fn only_whole_words_are_replaced() {
+        assert_eq!(replace_word("USD or usdt, USD.", "usd", "EUR"), "EUR or usdt, EUR.");
+    }
+}
@@ -0,0 +1,503 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::db::Db;
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum RateError {
+    #[error("No live exchange rate is available for {base} right now. Please try again in a minute or pay in INR.")]
+    Unavailable { base: String },
+    #[error("The latest {base} exchange rate is {age_secs} seconds old (limit {max_age_secs}). We won't price a payment with it; please try again shortly.")]
+    Stale { base: String, age_secs: i64, max_age_secs: i64 },
+    #[error("The {base} exchange rate just moved {deviation_pct:.1}% from its recent level ({rate:.4} vs {reference:.4}), beyond the {max_pct:.1}% we accept. We won't price a payment with it; please try again shortly.")]
+    Outlier { base: String, rate: f64, reference: f64, deviation_pct: f64, max_pct: f64 },
+}
+
+impl RateError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            RateError::Unavailable { .. } => "rate_unavailable",
+            RateError::Stale { .. } => "rate_stale",
+            RateError::Outlier { .. } => "rate_outlier",
+        }
+    }
+}
+
+// Exchange rates. Live rates come from exchangerate.host, which quotes
+// against USD, so base->INR = USDINR / USDBASE. INR is the pivot: rates to
+// INR are what is fetched, guarded, cached and recorded, and any other pair
+// is a cross rate through it (base->quote = base->INR / quote->INR). Rates are cached for
+// FX_CACHE_TTL_SECS (default 60) so the optimizer, quotes and payments price
+// with the same rate, and every accepted live rate is recorded in fx_rates.
+//
+// Every live rate passes a sanity guard before it is used or recorded:
+// - it is refused if the provider quoted it more than FX_MAX_AGE_SECS ago
+//   (default 900);
+// - it is refused if it deviates from the median of the last
+//   FX_GUARD_WINDOW (default 10) recorded rates of the last 24 hours by more
+//   than FX_MAX_DEVIATION_PCT (default 5). With none in 24 hours, the latest
+//   rate of the last 30 days is the reference; only a currency's very first
+//   rate is taken unchecked;
+// - a genuine move beyond the limit re-anchors the guard: once the provider
+//   has kept quoting the new level (GUARD_MIN_SAMPLES quotes, agreeing with
+//   each other) for FX_REANCHOR_MINS (default 30), it becomes the reference
+//   until the recorded history catches up.
+// A refusal or failed fetch is cached for FX_REFUSAL_CACHE_SECS (default 15)
+// so a bad provider isn't hammered. When no acceptable rate is available,
+// pricing fails closed with a RateError. Nothing falls back to made-up rates.
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Rate {
+    pub base: String,
+    pub quote: String,
+    /// Units of `quote` per unit of `base`.
+    pub rate: f64,
+    /// INR per unit of `quote` (1 for rates to INR), to value amounts in INR.
+    pub quote_inr: f64,
+    /// When the provider quoted the rate; None for the static fallback.
+    pub fetched_at: Option<DateTime<Utc>>,
+    pub provider: String,
+}
+
+impl Rate {
+    pub fn age_secs(&self, now: DateTime<Utc>) -> Option<i64> {
+        self.fetched_at.map(|t| (now - t).num_seconds().max(0))
+    }
+
+    /// base->quote from two rates to INR. The cross rate is as old as its
+    /// older leg; INR's own static leg does not count.
+    pub fn cross(base: &Rate, quote: &Rate) -> Rate {
+        let provider = if quote.provider == "static" || quote.provider == base.provider {
+            base.provider.clone()
+        } else if base.provider == "static" {
+            quote.provider.clone()
+        } else {
+            format!("{}+{}", base.provider, quote.provider)
+        };
+        let fetched_at = if base.provider == "static" {
+            quote.fetched_at
+        } else if quote.provider == "static" {
+            base.fetched_at
+        } else {
+            base.fetched_at.zip(quote.fetched_at).map(|(a, b)| a.min(b))
+        };
+        Rate {
+            base: base.base.clone(),
+            quote: quote.base.clone(),
+            rate: base.rate / quote.rate,
+            quote_inr: quote.rate,
+            fetched_at,
+            provider,
+        }
+    }
+}
+
+const LIVE_PROVIDER: &str = "exchangerate.host-live";
+/// How far back recorded rates count as the outlier reference.
+const GUARD_LOOKBACK_HOURS: i64 = 24;
+/// Without rates in the lookback, how far back the latest one may be.
+const GUARD_FALLBACK_DAYS: i64 = 30;
+/// Refused quotes needed before a move counts as persistent.
+const GUARD_MIN_SAMPLES: usize = 3;
+
+/// A fetch outcome and when it was cached.
+type Cached = (Result<Rate, RateError>, Instant);
+
+pub struct RateService {
+    http: reqwest::Client,
+    db: Db,
+    api_url: String,
+    api_key: Option<String>,
+    ttl: Duration,
+    refusal_ttl: Duration,
+    max_age_secs: i64,
+    guard_window: i64,
+    guard: Guard,
+    /// Accepted rates for `ttl`, refusals for `refusal_ttl`.
+    cache: Mutex<HashMap<String, Cached>>,
+}
+
+impl RateService {
+    pub fn from_env(db: Db) -> anyhow::Result<Self> {
+        let api_url = std::env::var("FX_API_URL").unwrap_or_else(|_| "https://api.exchangerate.host/live".to_string());
+        let api_key = std::env::var("FX_API_KEY").ok().map(|k| k.trim().to_string()).filter(|k| !k.is_empty());
+        if api_key.is_none() {
+            tracing::warn!("FX_API_KEY is not set; the rate provider will refuse requests and payments outside INR will fail");
+        }
+        let ttl: u64 = std::env::var("FX_CACHE_TTL_SECS").ok().and_then(|s| s.parse().ok()).unwrap_or(60);
+        let refusal_ttl: u64 = std::env::var("FX_REFUSAL_CACHE_SECS").ok().and_then(|s| s.parse().ok()).unwrap_or(15);
+        let max_age_secs: i64 = std::env::var("FX_MAX_AGE_SECS").ok().and_then(|s| s.parse().ok()).unwrap_or(900);
+        let max_deviation_pct: f64 = std::env::var("FX_MAX_DEVIATION_PCT").ok().and_then(|s| s.parse().ok()).unwrap_or(5.0);
+        let guard_window: i64 = std::env::var("FX_GUARD_WINDOW").ok().and_then(|s| s.parse().ok()).unwrap_or(10);
+        let reanchor_mins: i64 = std::env::var("FX_REANCHOR_MINS").ok().and_then(|s| s.parse().ok()).unwrap_or(30);
+        let http = reqwest::Client::builder().timeout(Duration::from_secs(5)).build()?;
+        Ok(Self {
+            http,
+            db,
+            api_url,
+            api_key,
+            ttl: Duration::from_secs(ttl),
+            refusal_ttl: Duration::from_secs(refusal_ttl),
+            max_age_secs,
+            guard_window,
+            guard: Guard::new(max_deviation_pct, chrono::Duration::minutes(reanchor_mins)),
+            cache: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub async fn rate_to_inr(&self, base: &str, now: DateTime<Utc>) -> Result<Rate, RateError> {
+        let base = base.trim().to_uppercase();
+        let mut rates = self.rates_to_inr(&[base.as_str()], now).await;
+        rates.remove(&base).unwrap_or(Err(RateError::Unavailable { base }))
+    }
+
+    /// Rates for several bases, fetching all cache misses in one request.
+    /// Bases without an acceptable live rate map to the reason.
+    pub async fn rates_to_inr(&self, bases: &[&str], now: DateTime<Utc>) -> HashMap<String, Result<Rate, RateError>> {
+        let mut out = HashMap::new();
+        let mut missing = Vec::new();
+        {
+            let cache = self.cache.lock().expect("fx cache poisoned");
+            for base in bases {
+                let base = base.trim().to_uppercase();
+                if base == "INR" {
+                    out.insert(base.clone(), Ok(Rate { base, quote: "INR".into(), rate: 1.0, quote_inr: 1.0, fetched_at: Some(now), provider: "static".into() }));
+                    continue;
+                }
+                match cache.get(&base) {
+                    Some((Ok(rate), cached_at)) if cached_at.elapsed() < self.ttl => {
+                        // A cached rate can age past the limit while cached
+                        out.insert(base, self.check_age(rate, now).map(|_| rate.clone()));
+                    }
+                    Some((Err(e), cached_at)) if cached_at.elapsed() < self.refusal_ttl => {
+                        out.insert(base, Err(e.clone()));
+                    }
+                    _ => missing.push(base),
+                }
+            }
+        }
+        if missing.is_empty() {
+            return out;
+        }
+        let live = match self.fetch_live(&missing).await {
+            Ok(live) => live,
+            Err(e) => {
+                tracing::warn!(error = %e, currencies = ?missing, "Live FX fetch failed");
+                HashMap::new()
+            }
+        };
+        for base in missing {
+            let result = match live.get(&base) {
+                Some(r) => self.guard(r, now).await.map(|_| r.clone()),
+                None => Err(RateError::Unavailable { base: base.clone() }),
+            };
+            match &result {
+                Ok(rate) => {
+                    if let Err(e) = self.db.insert_fx_rate(&base, "INR", rate.rate, Some(&rate.provider), rate.fetched_at).await {
+                        tracing::warn!(error = %e, base = %base, "fx rate insert failed");
+                    }
+                }
+                Err(e) => tracing::warn!(base = %base, code = e.code(), error = %e, "FX rate refused"),
+            }
+            self.cache.lock().expect("fx cache poisoned").insert(base.clone(), (result.clone(), Instant::now()));
+            out.insert(base, result);
+        }
+        out
+    }
+
+    /// base->quote for every base, as cross rates through INR. A base fails
+    /// if either its own leg or the quote currency's leg has no acceptable rate.
+    pub async fn rates(&self, bases: &[&str], quote: &str, now: DateTime<Utc>) -> HashMap<String, Result<Rate, RateError>> {
+        let quote = quote.trim().to_uppercase();
+        let mut wanted: Vec<&str> = bases.to_vec();
+        wanted.push(&quote);
+        let to_inr = self.rates_to_inr(&wanted, now).await;
+        let quote_leg = to_inr.get(&quote).cloned().unwrap_or(Err(RateError::Unavailable { base: quote.clone() }));
+        bases
+            .iter()
+            .map(|b| b.trim().to_uppercase())
+            .map(|b| {
+                let base_leg = to_inr.get(&b).cloned().unwrap_or(Err(RateError::Unavailable { base: b.clone() }));
+                let cross = base_leg.and_then(|base_leg| quote_leg.clone().map(|quote_leg| Rate::cross(&base_leg, &quote_leg)));
+                (b, cross)
+            })
+            .collect()
+    }
+
+    pub async fn rate(&self, base: &str, quote: &str, now: DateTime<Utc>) -> Result<Rate, RateError> {
+        let base = base.trim().to_uppercase();
+        let mut rates = self.rates(&[base.as_str()], quote, now).await;
+        rates.remove(&base).unwrap_or(Err(RateError::Unavailable { base }))
+    }
+
+    /// Staleness at `now`, the app clock, so a pinned GP_FIXED_NOW replays
+    /// the same verdicts.
+    fn check_age(&self, rate: &Rate, now: DateTime<Utc>) -> Result<(), RateError> {
+        let Some(age_secs) = rate.age_secs(now) else {
+            return Err(RateError::Unavailable { base: rate.base.clone() });
+        };
+        if age_secs > self.max_age_secs {
+            return Err(RateError::Stale { base: rate.base.clone(), age_secs, max_age_secs: self.max_age_secs });
+        }
+        Ok(())
+    }
+
+    /// Age and outlier checks for a freshly fetched rate.
+    async fn guard(&self, rate: &Rate, now: DateTime<Utc>) -> Result<(), RateError> {
+        self.check_age(rate, now)?;
+        let mut history = self.recorded(&rate.base, now - chrono::Duration::hours(GUARD_LOOKBACK_HOURS), self.guard_window).await?;
+        if history.is_empty() {
+            history = self.recorded(&rate.base, now - chrono::Duration::days(GUARD_FALLBACK_DAYS), 1).await?;
+        }
+        self.guard.check(rate, &history, now)
+    }
+
+    /// Recorded rates to INR since `since`, newest first. Without them the
+    /// outlier check can't run, so a database error refuses the rate.
+    async fn recorded(&self, base: &str, since: DateTime<Utc>, limit: i64) -> Result<Vec<f64>, RateError> {
+        self.db.recent_fx_rates(base, "INR", since, limit).await.map_err(|e| {
+            tracing::warn!(error = %e, base = %base, "fx history unavailable for outlier check");
+            RateError::Unavailable { base: base.to_string() }
+        })
+    }
+
+    async fn fetch_live(&self, bases: &[String]) -> anyhow::Result<HashMap<String, Rate>> {
+        let mut currencies = vec!["INR".to_string()];
+        currencies.extend(bases.iter().filter(|b| b.as_str() != "USD").cloned());
+        let url = match &self.api_key {
+            Some(key) => format!("{}?access_key={}&currencies={}", self.api_url, key, currencies.join(",")),
+            None => format!("{}?currencies={}", self.api_url, currencies.join(",")),
+        };
+        // Errors carry the URL, which includes the API key
+        let resp = self.http.get(&url).send().await.map_err(|e| e.without_url())?;
+        if !resp.status().is_success() {
+            anyhow::bail!("rate http status {}", resp.status());
+        }
+        let v: serde_json::Value = resp.json().await.map_err(|e| e.without_url())?;
+        if !v["success"].as_bool().unwrap_or(false) {
+            anyhow::bail!("rate api returned error: {}", v);
+        }
+        let quotes = v["quotes"].as_object().ok_or_else(|| anyhow::anyhow!("missing quotes"))?;
+        let usd_inr = quotes.get("USDINR").and_then(|x| x.as_f64()).ok_or_else(|| anyhow::anyhow!("missing USDINR"))?;
+        // Without the provider's timestamp the rate's age can't be checked
+        let ts = v["timestamp"].as_i64().ok_or_else(|| anyhow::anyhow!("missing timestamp"))?;
+        let fetched_at = DateTime::<Utc>::from_timestamp(ts, 0).ok_or_else(|| anyhow::anyhow!("bad timestamp {}", ts))?;
+        let mut out = HashMap::new();
+        for base in bases {
+            let rate = if base == "USD" {
+                Some(usd_inr)
+            } else {
+                quotes.get(&format!("USD{}", base)).and_then(|x| x.as_f64()).filter(|r| *r > 0.0).map(|usd_base| usd_inr / usd_base)
+            };
+            match rate {
+                Some(rate) => {
+                    out.insert(base.clone(), Rate { base: base.clone(), quote: "INR".into(), rate, quote_inr: 1.0, fetched_at: Some(fetched_at), provider: LIVE_PROVIDER.into() });
+                }
+                None => tracing::warn!(base = %base, "live FX response has no quote"),
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// The outlier check, on its own: a live rate against recorded history and
+/// any level a persistent move re-anchored to.
+struct Guard {
+    max_deviation_pct: f64,
+    /// How long the provider must keep quoting a refused level before it is
+    /// taken as a genuine move.
+    reanchor_after: chrono::Duration,
+    bases: Mutex<HashMap<String, GuardState>>,
+}
+
+#[derive(Default)]
+struct GuardState {
+    /// Reference set by a persistent move, until recorded history agrees.
+    anchor: Option<f64>,
+    /// Refused quotes (provider time, rate) at one new level.
+    refused: Vec<(DateTime<Utc>, f64)>,
+}
+
+fn deviation_pct(rate: f64, reference: f64) -> f64 {
+    (rate / reference - 1.0).abs() * 100.0
+}
+
+fn median(values: &[f64]) -> Option<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        n if n % 2 == 0 => Some((sorted[mid - 1] + sorted[mid]) / 2.0),
+        _ => Some(sorted[mid]),
+    }
+}
+
+impl Guard {
+    fn new(max_deviation_pct: f64, reanchor_after: chrono::Duration) -> Self {
+        Guard { max_deviation_pct, reanchor_after, bases: Mutex::new(HashMap::new()) }
+    }
+
+    /// Check `rate` against the median of `history` (recorded rates, any
+    /// number) or the re-anchored level.
+    fn check(&self, rate: &Rate, history: &[f64], now: DateTime<Utc>) -> Result<(), RateError> {
+        let mut bases = self.bases.lock().expect("fx guard poisoned");
+        let state = bases.entry(rate.base.clone()).or_default();
+        let median = median(history);
+        if let (Some(anchor), Some(median)) = (state.anchor, median) {
+            if deviation_pct(anchor, median) <= self.max_deviation_pct {
+                state.anchor = None;
+            }
+        }
+        let Some(reference) = state.anchor.or(median) else {
+            tracing::warn!(base = %rate.base, rate = rate.rate, "no recorded rate to compare with; first rate accepted unchecked");
+            return Ok(());
+        };
+        let deviation = deviation_pct(rate.rate, reference);
+        if deviation <= self.max_deviation_pct {
+            state.refused.clear();
+            return Ok(());
+        }
+        // Quotes at another level reset the count; a repeat of the same
+        // provider quote doesn't add to it
+        let quoted_at = rate.fetched_at.unwrap_or(now);
+        state.refused.retain(|(_, r)| deviation_pct(*r, rate.rate) <= self.max_deviation_pct);
+        if !state.refused.iter().any(|(t, _)| *t == quoted_at) {
+            state.refused.push((quoted_at, rate.rate));
+        }
+        let first = state.refused.iter().map(|(t, _)| *t).min().unwrap_or(quoted_at);
+        if state.refused.len() >= GUARD_MIN_SAMPLES && quoted_at - first >= self.reanchor_after {
+            tracing::warn!(base = %rate.base, rate = rate.rate, reference, since = %first, "FX guard re-anchored after a persistent move");
+            state.anchor = Some(rate.rate);
+            state.refused.clear();
+            return Ok(());
+        }
+        Err(RateError::Outlier { base: rate.base.clone(), rate: rate.rate, reference, deviation_pct: deviation, max_pct: self.max_deviation_pct })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    fn rate(base: &str, to_inr: f64, fetched_at: Option<DateTime<Utc>>, provider: &str) -> Rate {
+        Rate { base: base.into(), quote: "INR".into(), rate: to_inr, quote_inr: 1.0, fetched_at, provider: provider.into() }
+    }
+
+    fn guard() -> Guard {
+        Guard::new(5.0, chrono::Duration::minutes(30))
+    }
+
+    /// Checks an AED quote the provider made at `quoted_at`, seen then.
+    fn check(g: &Guard, to_inr: f64, history: &[f64], quoted_at: &str) -> Result<(), RateError> {
+        let now = at(quoted_at);
+        g.check(&rate("AED", to_inr, Some(now), LIVE_PROVIDER), history, now)
+    }
+
+    #[test]
+    fn median_of_recorded_rates() {
+        assert_eq!(median(&[]), None);
+        assert_eq!(median(&[3.0, 1.0, 2.0]), Some(2.0));
+        assert_eq!(median(&[4.0, 1.0, 2.0, 3.0]), Some(2.5));
+    }
+
+    #[test]
+    fn the_first_rate_of_a_currency_is_taken_unchecked() {
+        assert!(check(&guard(), 22.6, &[], "2026-10-19T06:00:00Z").is_ok());
+    }
+
+    #[test]
+    fn outliers_are_refused_even_against_one_recorded_rate() {
+        let g = guard();
+        assert!(check(&g, 23.5, &[22.6], "2026-10-19T06:00:00Z").is_ok());
+        match check(&g, 27.0, &[22.6], "2026-10-19T06:00:00Z") {
+            Err(RateError::Outlier { reference, deviation_pct, .. }) => {
+                assert_eq!(reference, 22.6);
+                assert!(deviation_pct > 19.0);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        // The median, not the mean, is the reference
+        assert!(check(&g, 22.7, &[22.6, 22.7, 90.0], "2026-10-19T06:00:00Z").is_ok());
+    }
+
+    #[test]
+    fn a_persistent_move_re_anchors_the_guard() {
+        let g = guard();
+        let history = [22.6, 22.6, 22.6];
+        // Refused at first, and a repeat of the same provider quote doesn't count
+        assert!(check(&g, 25.0, &history, "2026-10-19T06:00:00Z").is_err());
+        assert!(check(&g, 25.0, &history, "2026-10-19T06:00:00Z").is_err());
+        // Three quotes at the new level, but within 30 minutes
+        assert!(check(&g, 25.1, &history, "2026-10-19T06:10:00Z").is_err());
+        assert!(check(&g, 25.05, &history, "2026-10-19T06:20:00Z").is_err());
+        // 30 minutes on, the move is accepted and becomes the reference
+        assert!(check(&g, 25.0, &history, "2026-10-19T06:30:00Z").is_ok());
+        assert!(check(&g, 25.2, &history, "2026-10-19T06:31:00Z").is_ok());
+        assert!(check(&g, 22.6, &history, "2026-10-19T06:32:00Z").is_err());
+        // Once recorded history has caught up the anchor is dropped
+        assert!(check(&g, 25.1, &[25.0, 25.1, 25.2], "2026-10-19T07:00:00Z").is_ok());
+        assert!(g.bases.lock().unwrap()["AED"].anchor.is_none());
+    }
+
+    #[test]
+    fn erratic_quotes_never_re_anchor() {
+        let g = guard();
+        let history = [22.6, 22.6, 22.6];
+        // Each quote is far from the one before, so the count restarts
+        for (i, level) in [25.0, 30.0, 25.0, 30.0, 25.0].into_iter().enumerate() {
+            let now = at("2026-10-19T06:00:00Z") + chrono::Duration::minutes(20 * i as i64);
+            assert!(g.check(&rate("AED", level, Some(now), LIVE_PROVIDER), &history, now).is_err());
+        }
+        // An accepted rate clears refusals, so a move has to start over
+        let g = guard();
+        assert!(check(&g, 25.0, &history, "2026-10-19T06:00:00Z").is_err());
+        assert!(check(&g, 25.0, &history, "2026-10-19T06:20:00Z").is_err());
+        assert!(check(&g, 22.7, &history, "2026-10-19T06:25:00Z").is_ok());
+        assert!(check(&g, 25.0, &history, "2026-10-19T06:40:00Z").is_err());
+    }
+
+    #[test]
+    fn undated_quotes_are_timed_by_the_app_clock() {
+        let g = guard();
+        let history = [22.6, 22.6, 22.6];
+        for minutes in [0, 15, 30] {
+            let now = at("2026-10-19T06:00:00Z") + chrono::Duration::minutes(minutes);
+            let verdict = g.check(&rate("AED", 25.0, None, LIVE_PROVIDER), &history, now);
+            assert_eq!(verdict.is_ok(), minutes == 30);
+        }
+    }
+
+    #[test]
+    fn cross_rates_go_through_inr() {
+        let aed = rate("AED", 22.6, Some(at("2026-10-19T06:00:00Z")), "live");
+        let npr = rate("NPR", 0.625, Some(at("2026-10-19T05:58:00Z")), "live");
+        let cross = Rate::cross(&aed, &npr);
+        assert_eq!((cross.base.as_str(), cross.quote.as_str()), ("AED", "NPR"));
+        assert!((cross.rate - 36.16).abs() < 1e-9);
+        assert_eq!(cross.quote_inr, 0.625);
+        // As old as the older leg
+        assert_eq!(cross.fetched_at, Some(at("2026-10-19T05:58:00Z")));
+        assert_eq!(cross.provider, "live");
+    }
+
+    #[test]
+    fn cross_rates_ignore_the_static_inr_leg() {
+        let aed = rate("AED", 22.6, Some(at("2026-10-19T06:00:00Z")), "live");
+        let inr = rate("INR", 1.0, None, "static");
+        let cross = Rate::cross(&aed, &inr);
+        assert_eq!(cross.rate, 22.6);
+        assert_eq!(cross.fetched_at, aed.fetched_at);
+        assert_eq!(cross.provider, "live");
+        let mixed = Rate::cross(&aed, &rate("EUR", 90.0, Some(at("2026-10-19T06:01:00Z")), "other"));
+        assert_eq!(mixed.provider, "live+other");
+    }
+}
@@ -0,0 +1,49 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+
+// Offline GeoIP: resolves an IP to an ISO 3166-1 alpha-2 country code from a
+// local MaxMind-format database (e.g. GeoLite2-Country.mmdb). Optional; when
+// GEOIP_DB_PATH is unset or unreadable, lookups are simply skipped.
+pub struct GeoIp {
+    reader: maxminddb::Reader<Vec<u8>>,
+}
+
+impl GeoIp {
+    pub fn open(path: &str) -> anyhow::Result<Self> {
+        let reader = maxminddb::Reader::open_readfile(path)
+            .map_err(|e| anyhow::anyhow!("cannot open GeoIP database {}: {}", path, e))?;
+        Ok(Self { reader })
+    }
+
+    pub fn country(&self, ip: IpAddr) -> Option<String> {
+        if !is_public(ip) {
+            return None;
+        }
+        let rec: maxminddb::geoip2::Country = self.reader.lookup(ip).ok()?;
+        rec.country
+            .or(rec.registered_country)
+            .and_then(|c| c.iso_code)
+            .map(|c| c.to_uppercase())
+    }
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => !(v4.is_private() || v4.is_loopback() || v4.is_link_local() || v4.is_unspecified() || v4.is_broadcast()),
+        IpAddr::V6(v6) => !(v6.is_loopback() || v6.is_unspecified() || (v6.segments()[0] & 0xfe00) == 0xfc00),
+    }
+}
+
+pub fn from_env() -> Option<Arc<GeoIp>> {
+    let path = std::env::var("GEOIP_DB_PATH").ok().filter(|p| !p.trim().is_empty())?;
+    match GeoIp::open(path.trim()) {
+        Ok(g) => {
+            tracing::info!(path = %path.trim(), "GeoIP database loaded");
+            Some(Arc::new(g))
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "GeoIP disabled");
+            None
+        }
+    }
+}
@@ -0,0 +1,334 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use rust_stemmers::{Algorithm, Stemmer};
+use serde::Serialize;
+
+use crate::lang;
+
+// Markdown knowledge base for the /ask assistant. Articles live in KB_DIR
+// (default ./kb) with one directory per language (`kb/en`, `kb/hi`, ...);
+// each `## ` section becomes a passage ranked with BM25 over stemmed,
+// synonym-folded tokens. Editors change the files and hit
+// POST /admin/kb/reload (or restart) — no recompile needed.
+
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Passage {
+    pub article: String,
+    pub title: String,
+    pub heading: Option<String>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Hit {
+    #[serde(flatten)]
+    pub passage: Passage,
+    pub score: f64,
+}
+
+pub struct KnowledgeBase {
+    passages: Vec<Passage>,
+    term_freqs: Vec<HashMap<String, u32>>,
+    doc_lens: Vec<f64>,
+    avg_len: f64,
+    doc_freq: HashMap<String, u32>,
+    synonyms: HashMap<String, String>,
+    stopwords: &'static [&'static str],
+    /// Snowball stemmer where one exists (English, Arabic); other scripts
+    /// match on folded whole words plus synonyms.
+    stemmer: Option<Stemmer>,
+}
+
+impl KnowledgeBase {
+    /// Load every `*.md` in `dir` as `lang` content, plus optional
+    /// `synonyms.txt` (one comma-separated group per line; the first word is
+    /// canonical).
+    pub fn load(root: &Path, lang: &str) -> anyhow::Result<Self> {
+        let mut synonyms = HashMap::new();
+        let syn_path = root.join("synonyms.txt");
+        if syn_path.exists() {
+            for line in std::fs::read_to_string(&syn_path)?.lines() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') { continue; }
+                let words: Vec<String> = line.split(',').map(|w| lang::normalize(w.trim())).filter(|w| !w.is_empty()).collect();
+                if let Some(canonical) = words.first() {
+                    for w in &words {
+                        synonyms.insert(w.clone(), canonical.clone());
+                    }
+                }
+            }
+        }
+        let mut files: Vec<_> = std::fs::read_dir(root)
+            .map_err(|e| anyhow::anyhow!("cannot read knowledge base {}: {}", root.display(), e))?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().and_then(|x| x.to_str()) == Some("md"))
+            .collect();
+        files.sort();
+        let mut passages = Vec::new();
+        for path in files {
+            let slug = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default().to_string();
+            let raw = std::fs::read_to_string(&path)?;
+            passages.extend(split_article(&slug, &raw));
+        }
+        Ok(Self::build(passages, synonyms, lang))
+    }
+
+    fn build(passages: Vec<Passage>, synonyms: HashMap<String, String>, lang: &str) -> Self {
+        let stemmer = match lang {
+            "en" => Some(Stemmer::create(Algorithm::English)),
+            "ar" => Some(Stemmer::create(Algorithm::Arabic)),
+            _ => None,
+        };
+        let mut kb = Self {
+            passages: Vec::new(),
+            term_freqs: Vec::new(),
+            doc_lens: Vec::new(),
+            avg_len: 0.0,
+            doc_freq: HashMap::new(),
+            synonyms,
+            stopwords: lang::stopwords(lang),
+            stemmer,
+        };
+        for p in &passages {
+            // Title and heading words count towards the passage
+            let indexed = format!("{} {} {}", p.title, p.heading.as_deref().unwrap_or(""), p.text);
+            let terms = kb.terms(&indexed);
+            let mut tf: HashMap<String, u32> = HashMap::new();
+            for t in &terms {
+                *tf.entry(t.clone()).or_default() += 1;
+            }
+            for t in tf.keys() {
+                *kb.doc_freq.entry(t.clone()).or_default() += 1;
+            }
+            kb.doc_lens.push(terms.len() as f64);
+            kb.term_freqs.push(tf);
+        }
+        kb.avg_len = if kb.doc_lens.is_empty() { 0.0 } else { kb.doc_lens.iter().sum::<f64>() / kb.doc_lens.len() as f64 };
+        kb.passages = passages;
+        kb
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.passages.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.passages.len()
+    }
+
+    fn terms(&self, text: &str) -> Vec<String> {
+        tokenize(text)
+            .into_iter()
+            .filter(|t| !self.stopwords.contains(&t.as_str()))
+            .map(|t| {
+                let folded = self.synonyms.get(&t).cloned().unwrap_or(t);
+                match &self.stemmer {
+                    Some(stemmer) => stemmer.stem(&folded).into_owned(),
+                    None => folded,
+                }
+            })
+            .collect()
+    }
+
+    /// Top `k` passages by BM25 score (only those sharing at least one term).
+    pub fn search(&self, query: &str, k: usize) -> Vec<Hit> {
+        let mut q = self.terms(query);
+        q.sort();
+        q.dedup();
+        let n = self.passages.len() as f64;
+        let mut scored: Vec<(usize, f64)> = self
+            .term_freqs
+            .iter()
+            .enumerate()
+            .map(|(i, tf)| {
+                let len_norm = 1.0 - B + B * self.doc_lens[i] / self.avg_len.max(1.0);
+                let score = q
+                    .iter()
+                    .filter_map(|t| {
+                        let f = *tf.get(t)? as f64;
+                        let df = *self.doc_freq.get(t)? as f64;
+                        let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+                        Some(idf * f * (K1 + 1.0) / (f + K1 * len_norm))
+                    })
+                    .sum::<f64>();
+                (i, score)
+            })
+            .filter(|(_, s)| *s > 0.0)
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored
+            .into_iter()
+            .take(k)
+            .map(|(i, score)| Hit { passage: self.passages[i].clone(), score })
+            .collect()
+    }
+}
+
+/// Knowledge bases for every language found under KB_DIR.
+pub struct Library {
+    bases: HashMap<&'static str, KnowledgeBase>,
+}
+
+impl Library {
+    pub fn empty() -> Self {
+        Self { bases: HashMap::new() }
+    }
+
+    pub fn dir_from_env() -> String {
+        std::env::var("KB_DIR").unwrap_or_else(|_| "kb".to_string())
+    }
+
+    /// Load `dir/<lang>/` for each supported language. A flat directory of
+    /// `*.md` files (the pre-multilingual layout) is loaded as English.
+    pub fn load(dir: &str) -> anyhow::Result<Self> {
+        let root = Path::new(dir);
+        let mut bases = HashMap::new();
+        for lang in lang::SUPPORTED {
+            let sub = root.join(lang);
+            if sub.is_dir() {
+                bases.insert(*lang, KnowledgeBase::load(&sub, lang)?);
+            }
+        }
+        if bases.is_empty() {
+            bases.insert("en", KnowledgeBase::load(root, "en")?);
+        }
+        Ok(Self { bases })
+    }
+
+    pub fn get(&self, lang: &str) -> Option<&KnowledgeBase> {
+        self.bases.get(lang)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bases.values().all(KnowledgeBase::is_empty)
+    }
+
+    /// Passage count per language.
+    pub fn counts(&self) -> HashMap<&'static str, usize> {
+        self.bases.iter().map(|(l, kb)| (*l, kb.len())).collect()
+    }
+}
+
+/// Split on anything that isn't part of a word (see `lang::is_word_char`) and
+/// fold each token with `lang::normalize`.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !lang::is_word_char(c))
+        .filter(|t| !t.is_empty())
+        .map(lang::normalize)
+        .collect()
+}
+
+// "# Title" starts the article; each "## Heading" starts a new passage.
+fn split_article(slug: &str, raw: &str) -> Vec<Passage> {
+    let mut title = slug.replace('-', " ");
+    let mut out = Vec::new();
+    let mut heading: Option<String> = None;
+    let mut buf: Vec<&str> = Vec::new();
+    let flush = |heading: &Option<String>, buf: &mut Vec<&str>, title: &str, out: &mut Vec<Passage>| {
+        let text = clean_markdown(&buf.join("\n"));
+        if !text.is_empty() {
+            out.push(Passage { article: slug.to_string(), title: title.to_string(), heading: heading.clone(), text });
+        }
+        buf.clear();
+    };
+    for line in raw.lines() {
+        if let Some(t) = line.strip_prefix("# ") {
+            title = t.trim().to_string();
+        } else if let Some(h) = line.strip_prefix("## ") {
+            flush(&heading, &mut buf, &title, &mut out);
+            heading = Some(h.trim().to_string());
+        } else {
+            buf.push(line);
+        }
+    }
+    flush(&heading, &mut buf, &title, &mut out);
+    out
+}
+
+fn clean_markdown(s: &str) -> String {
+    s.lines()
+        .map(|l| l.trim().trim_start_matches("- ").trim_start_matches("* "))
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace(['`', '*'], "")
+}
+
+/// Map a BM25 score to a rough 0..1 confidence for display.
+pub fn confidence(score: f64) -> f64 {
+    (score / (score + 4.0) * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEES: &str = "# Fees\n\nIntro line.\n\n## Transfer fee\n- A flat `₹99` per **transfer**.\n\n## Platform fee\nA ₹25 charge on every payment.\n";
+    const RATES: &str = "# Exchange rates\n\n## Where rates come from\nLive conversion rates from the provider, refreshed every minute.\n";
+
+    fn kb(synonyms: &[(&str, &str)]) -> KnowledgeBase {
+        let mut passages = split_article("fees", FEES);
+        passages.extend(split_article("fx-rates", RATES));
+        let synonyms = synonyms.iter().map(|(w, c)| (w.to_string(), c.to_string())).collect();
+        KnowledgeBase::build(passages, synonyms, "en")
+    }
+
+    #[test]
+    fn articles_split_into_passages_per_heading() {
+        let passages = split_article("fees", FEES);
+        assert_eq!(passages.len(), 3);
+        assert_eq!((passages[0].title.as_str(), passages[0].heading.as_deref(), passages[0].text.as_str()), ("Fees", None, "Intro line."));
+        assert_eq!(passages[1].heading.as_deref(), Some("Transfer fee"));
+        assert_eq!(passages[1].text, "A flat ₹99 per transfer.");
+        // Without a "# " line the slug is the title
+        assert_eq!(split_article("upi-demo", "Some text")[0].title, "upi demo");
+    }
+
+    #[test]
+    fn tokens_split_on_punctuation_and_fold() {
+        assert_eq!(tokenize("Why ₹124 EXTRA?"), ["why", "124", "extra"]);
+        assert_eq!(tokenize("शुल्क कितना है?"), ["शुल्क", "कितना", "है"]);
+    }
+
+    #[test]
+    fn search_ranks_by_shared_stemmed_terms() {
+        let kb = kb(&[]);
+        let hits = kb.search("what are the transfer fees?", 2);
+        assert_eq!(hits[0].passage.heading.as_deref(), Some("Transfer fee"));
+        assert!(hits.iter().all(|h| h.passage.article == "fees"));
+        // Stopwords alone match nothing
+        assert!(kb.search("what is the", 3).is_empty());
+    }
+
+    #[test]
+    fn synonyms_fold_to_their_canonical_word() {
+        assert!(kb(&[]).search("forex", 1).is_empty());
+        let hits = kb(&[("forex", "conversion")]).search("forex", 1);
+        assert_eq!(hits[0].passage.article, "fx-rates");
+    }
+
+    #[test]
+    fn confidence_grows_with_the_score() {
+        assert_eq!(confidence(0.0), 0.0);
+        assert_eq!(confidence(4.0), 0.5);
+        assert!(confidence(12.0) > confidence(4.0) && confidence(1e6) <= 1.0);
+    }
+
+    #[test]
+    fn the_shipped_knowledge_base_covers_every_language() {
+        let library = Library::load("kb").unwrap();
+        for lang in lang::SUPPORTED {
+            assert!(library.get(lang).is_some_and(|kb| !kb.is_empty()), "{}", lang);
+        }
+    }
+
+    #[test]
+    fn the_flat_layout_loads_as_english() {
+        let library = Library::load("kb/en").unwrap();
+        assert_eq!(library.counts().into_keys().collect::<Vec<_>>(), ["en"]);
+    }
+}
@@ -0,0 +1,166 @@
+// Languages the assistant answers in, for the NPR/LKR/AED/BTN/MUR corridors.
+// Detection is by script: Sinhala and Arabic are unambiguous, Devanagari is
+// split between Hindi and Nepali by common function words. Latin-script
+// questions are treated as English.
+
+pub const SUPPORTED: &[&str] = &["en", "hi", "ne", "si", "ar"];
+
+pub fn name(lang: &str) -> &'static str {
+    match lang {
+        "hi" => "Hindi",
+        "ne" => "Nepali",
+        "si" => "Sinhala",
+        "ar" => "Arabic",
+        _ => "English",
+    }
+}
+
+/// Canonical code for a client hint like "ne-NP" or "AR", if supported.
+pub fn supported(hint: &str) -> Option<&'static str> {
+    let base = hint.trim().split(['-', '_']).next().unwrap_or("").to_ascii_lowercase();
+    SUPPORTED.iter().copied().find(|l| *l == base)
+}
+
+const HINDI_MARKERS: &[&str] = &["है", "हैं", "क्या", "मेरा", "मेरी", "मुझे", "कितना", "कितनी", "क्यों", "कैसे", "में", "नहीं", "रुपये", "होता", "लगता", "भेजने"];
+const NEPALI_MARKERS: &[&str] = &["छ", "छन्", "हुन्छ", "गर्न", "गर्ने", "मेरो", "कति", "लाई", "किन", "कसरी", "भयो", "रुपैयाँ", "तिर्नु", "पठाउन", "हो"];
+
+/// Language of `text`, using `hint` only to break a Hindi/Nepali tie.
+pub fn detect(text: &str, hint: Option<&str>) -> &'static str {
+    let (mut deva, mut sinh, mut arab) = (0usize, 0usize, 0usize);
+    for c in text.chars() {
+        match c as u32 {
+            0x0900..=0x097F => deva += 1,
+            0x0D80..=0x0DFF => sinh += 1,
+            0x0600..=0x06FF | 0x0750..=0x077F | 0xFB50..=0xFDFF | 0xFE70..=0xFEFF => arab += 1,
+            _ => {}
+        }
+    }
+    if deva == 0 && sinh == 0 && arab == 0 {
+        return "en";
+    }
+    if sinh >= deva && sinh >= arab {
+        return "si";
+    }
+    if arab >= deva {
+        return "ar";
+    }
+    let words = crate::kb::tokenize(text);
+    let count = |markers: &[&str]| words.iter().filter(|w| markers.contains(&w.as_str())).count();
+    let (hi, ne) = (count(HINDI_MARKERS), count(NEPALI_MARKERS));
+    if ne > hi {
+        "ne"
+    } else if hi > ne {
+        "hi"
+    } else {
+        match hint.and_then(supported) {
+            Some("ne") => "ne",
+            _ => "hi",
+        }
+    }
+}
+
+// Vowel signs, viramas and Arabic harakat are combining marks rather than
+// letters; splitting on them would cut words like "हुन्छ" in half.
+fn is_mark(c: char) -> bool {
+    matches!(c as u32,
+        0x0300..=0x036F
+        | 0x0610..=0x061A | 0x064B..=0x065F | 0x0670 | 0x06D6..=0x06DC | 0x06DF..=0x06E4 | 0x06E7..=0x06E8 | 0x06EA..=0x06ED
+        | 0x0900..=0x0903 | 0x093A..=0x093C | 0x093E..=0x094F | 0x0951..=0x0957 | 0x0962..=0x0963
+        | 0x0D81..=0x0D83 | 0x0DCA | 0x0DCF..=0x0DD4 | 0x0DD6 | 0x0DD8..=0x0DDF | 0x0DF2..=0x0DF3)
+}
+
+/// Part of a word: letters, digits, combining marks and the zero-width
+/// joiners Sinhala and Devanagari use inside conjuncts.
+pub fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || is_mark(c) || c == '\u{200C}' || c == '\u{200D}'
+}
+
+/// Map Devanagari, Arabic-Indic and Sinhala digits to ASCII.
+pub fn ascii_digit(c: char) -> char {
+    let zero = match c as u32 {
+        0x0966..=0x096F => 0x0966,
+        0x0660..=0x0669 => 0x0660,
+        0x06F0..=0x06F9 => 0x06F0,
+        0x0DE6..=0x0DEF => 0x0DE6,
+        _ => return c,
+    };
+    char::from(b'0' + (c as u32 - zero) as u8)
+}
+
+pub fn ascii_digits(s: &str) -> String {
+    s.chars().map(ascii_digit).collect()
+}
+
+/// Fold a token for matching: lowercase, ASCII digits, no zero-width joiners,
+/// and Arabic without tatweel/harakat and with alef, ya and ta marbuta unified.
+pub fn normalize(token: &str) -> String {
+    token
+        .to_lowercase()
+        .chars()
+        .filter(|c| !matches!(*c as u32, 0x200C | 0x200D | 0x0640 | 0x064B..=0x065F | 0x0670))
+        .map(|c| match c {
+            'أ' | 'إ' | 'آ' | 'ٱ' => 'ا',
+            'ى' => 'ي',
+            'ة' => 'ه',
+            other => ascii_digit(other),
+        })
+        .collect()
+}
+
+/// Words ignored by retrieval, already in `normalize`d form.
+pub fn stopwords(lang: &str) -> &'static [&'static str] {
+    match lang {
+        "hi" => &["है", "हैं", "क्या", "का", "की", "के", "में", "से", "को", "और", "मेरा", "मेरी", "मुझे", "कैसे", "यह", "हो", "पर", "लिए", "था", "तो", "भी", "एक"],
+        "ne" => &["छ", "छन्", "के", "को", "मा", "र", "मेरो", "हो", "लाई", "कसरी", "यो", "पनि", "हुन्छ", "गर्न", "एक", "त"],
+        "si" => &["ද", "මට", "මගේ", "සහ", "ඇයි", "මොකක්ද", "කොහොමද", "එක", "මේ", "ඒ", "වෙනවා", "තියෙනවා", "කියන්න"],
+        "ar" => &["ما", "ماذا", "هل", "في", "من", "علي", "كيف", "هي", "هو", "ان", "الي", "عن", "لماذا", "مع", "هذا", "هذه", "لي"],
+        _ => &[
+            "a", "an", "and", "are", "as", "at", "be", "by", "can", "do", "does", "for", "from", "how", "i", "in", "is", "it",
+            "my", "of", "on", "or", "the", "this", "to", "what", "when", "why", "will", "with", "you", "your", "me", "we",
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latin_script_is_english_whatever_the_hint() {
+        assert_eq!(detect("Why did I pay extra?", Some("hi")), "en");
+        assert_eq!(detect("", None), "en");
+    }
+
+    #[test]
+    fn devanagari_is_split_by_common_words() {
+        assert_eq!(detect("मुझे ज्यादा शुल्क क्यों लगा है?", None), "hi");
+        assert_eq!(detect("मेरो शुल्क किन बढी भयो?", None), "ne");
+        // A tie goes to the hint, else Hindi
+        assert_eq!(detect("शुल्क दर", Some("ne-NP")), "ne");
+        assert_eq!(detect("शुल्क दर", Some("si")), "hi");
+        assert_eq!(detect("शुल्क दर", None), "hi");
+    }
+
+    #[test]
+    fn sinhala_and_arabic_are_told_by_script() {
+        assert_eq!(detect("මගේ ගාස්තුව ඇයි වැඩි?", Some("hi")), "si");
+        assert_eq!(detect("لماذا الرسوم مرتفعة؟", None), "ar");
+        // The script most of the text is in wins over a stray English word
+        assert_eq!(detect("UPI ගාස්තුව කීයද?", None), "si");
+        assert_eq!(detect("مرحبا fee", None), "ar");
+    }
+
+    #[test]
+    fn hints_are_matched_on_their_base_language() {
+        assert_eq!(supported("ne-NP"), Some("ne"));
+        assert_eq!(supported(" AR "), Some("ar"));
+        assert_eq!(supported("fr"), None);
+    }
+
+    #[test]
+    fn tokens_are_folded_for_matching() {
+        assert_eq!(normalize("₹१२४"), "₹124");
+        assert_eq!(normalize("أسعار"), "اسعار");
+        assert_eq!(normalize("FEES"), "fees");
+    }
+}
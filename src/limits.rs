@@ -0,0 +1,296 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+
+use crate::currency::Registry;
+use crate::pricing::Quote;
+
+// Transaction limits, loaded from LIMITS_FILE (default config/limits.json):
+// - per corridor, the smallest and largest amount one payment may send, in
+//   the source currency (on top of each currency's own limits);
+// - per payer, caps on what they may send over rolling daily (24h), monthly
+//   (30 days) and yearly (365 days) windows, modelled on RBI's Liberalised
+//   Remittance Scheme ($250,000 a year). Usage is the INR value of the
+//   receiver credits of the payer's past payments, compared in the limit
+//   currency at today's rate, or at the `min_rate_inr` floor when the payment
+//   stays under the caps even there.
+// There are no payer accounts: payments are counted per device fingerprint
+// and per client IP, and the larger total applies, so a fresh fingerprint or
+// a new IP alone doesn't reset the caps.
+// Payers are told which limit a payment breaks and by how much.
+
+#[derive(Debug, thiserror::Error)]
+pub enum LimitError {
+    /// Outside the source currency's own limits (from the currencies file).
+    #[error("{0}")]
+    Currency(String),
+    #[error("Payments from {source_currency} to {dest_currency} must be at least {min} {source_currency}.")]
+    BelowMinimum { source_currency: String, dest_currency: String, min: String },
+    #[error("Payments from {source_currency} to {dest_currency} can be at most {max} {source_currency}.")]
+    AboveMaximum { source_currency: String, dest_currency: String, max: String },
+    #[error("This payment would take you over your {} limit of {limit:.2} {currency}: you have sent {used:.2} {currency} in the last {}, so at most {remaining:.2} {currency} more.", .window.name(), .window.span())]
+    Payer { window: Window, currency: String, limit: f64, used: f64, remaining: f64 },
+}
+
+impl LimitError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            LimitError::Currency(_) => "currency_limit",
+            LimitError::BelowMinimum { .. } => "corridor_minimum",
+            LimitError::AboveMaximum { .. } => "corridor_maximum",
+            LimitError::Payer { window: Window::Daily, .. } => "payer_daily_limit",
+            LimitError::Payer { window: Window::Monthly, .. } => "payer_monthly_limit",
+            LimitError::Payer { window: Window::Yearly, .. } => "payer_yearly_limit",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Window {
+    Daily,
+    Monthly,
+    Yearly,
+}
+
+impl Window {
+    pub const ALL: [Window; 3] = [Window::Daily, Window::Monthly, Window::Yearly];
+
+    pub fn duration(self) -> Duration {
+        match self {
+            Window::Daily => Duration::hours(24),
+            Window::Monthly => Duration::days(30),
+            Window::Yearly => Duration::days(365),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Window::Daily => "daily",
+            Window::Monthly => "monthly",
+            Window::Yearly => "yearly",
+        }
+    }
+
+    fn span(self) -> &'static str {
+        match self {
+            Window::Daily => "24 hours",
+            Window::Monthly => "30 days",
+            Window::Yearly => "365 days",
+        }
+    }
+}
+
+/// A key cumulative limits are counted under.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Payer<'a> {
+    /// Browser fingerprint, as stored in `payments.device_fp`.
+    Device(&'a str),
+    /// Client IP: every payment from it counts, fingerprinted or not.
+    Ip(&'a str),
+}
+
+impl<'a> Payer<'a> {
+    /// The keys a payment is counted under: its device and its IP, whichever
+    /// are known.
+    pub fn all(device_fp: Option<&'a str>, client_ip: Option<&'a str>) -> Vec<Self> {
+        device_fp.map(Payer::Device).into_iter().chain(client_ip.map(Payer::Ip)).collect()
+    }
+}
+
+/// Per-window usage of the payer: the largest of the totals counted under
+/// each of their keys.
+pub fn heaviest(totals: &[[f64; 3]]) -> [f64; 3] {
+    totals.iter().fold([0.0; 3], |acc, t| [acc[0].max(t[0]), acc[1].max(t[1]), acc[2].max(t[2])])
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CorridorLimit {
+    /// "AED>NPR", as in CORRIDORS.
+    pub corridor: String,
+    pub min_amount: Option<f64>,
+    pub max_amount: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PayerLimits {
+    /// Currency the caps are set in, e.g. "USD".
+    pub currency: String,
+    pub daily: Option<f64>,
+    pub monthly: Option<f64>,
+    pub yearly: Option<f64>,
+    /// A floor on INR per unit of `currency`. A payment under the caps at
+    /// this rate is under them at any higher one, so it needs no live rate
+    /// (INR payments go through without the FX provider); without a live
+    /// rate the caps are checked at it.
+    pub min_rate_inr: Option<f64>,
+}
+
+impl PayerLimits {
+    fn cap(&self, window: Window) -> Option<f64> {
+        match window {
+            Window::Daily => self.daily,
+            Window::Monthly => self.monthly,
+            Window::Yearly => self.yearly,
+        }
+    }
+
+    /// Check a payment worth `amount_inr` against the payer's past receiver
+    /// credits per window (`used_inr`, in `Window::ALL` order), with
+    /// `currency_inr` INR per unit of the limit currency.
+    pub fn check(&self, amount_inr: f64, used_inr: [f64; 3], currency_inr: f64) -> Result<(), LimitError> {
+        for (window, used_inr) in Window::ALL.into_iter().zip(used_inr) {
+            let Some(limit) = self.cap(window) else { continue };
+            let used = used_inr / currency_inr;
+            if used + amount_inr / currency_inr > limit + 1e-9 {
+                return Err(LimitError::Payer { window, currency: self.currency.clone(), limit, used, remaining: (limit - used).max(0.0) });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Limits {
+    #[serde(default)]
+    pub corridors: Vec<CorridorLimit>,
+    pub payer: Option<PayerLimits>,
+}
+
+impl Limits {
+    pub fn from_env(currencies: &Registry) -> anyhow::Result<Self> {
+        let path = std::env::var("LIMITS_FILE").unwrap_or_else(|_| "config/limits.json".to_string());
+        let raw = std::fs::read_to_string(&path).map_err(|e| anyhow::anyhow!("cannot read limits file {}: {}", path, e))?;
+        let mut limits: Limits = serde_json::from_str(&raw).map_err(|e| anyhow::anyhow!("invalid limits file {}: {}", path, e))?;
+        for c in &mut limits.corridors {
+            c.corridor = c.corridor.replace(' ', "").to_uppercase();
+            let (src, dest) = c.corridor.split_once('>').ok_or_else(|| anyhow::anyhow!("limits: corridor {:?} must look like AED>INR", c.corridor))?;
+            if currencies.get(src).is_none() || currencies.get(dest).is_none() {
+                anyhow::bail!("limits: corridor {} uses a currency that is not in the currencies file", c.corridor);
+            }
+            if let (Some(min), Some(max)) = (c.min_amount, c.max_amount) {
+                if min > max {
+                    anyhow::bail!("limits: corridor {} has min_amount above max_amount", c.corridor);
+                }
+            }
+        }
+        if let Some(p) = &mut limits.payer {
+            p.currency = p.currency.trim().to_uppercase();
+            if currencies.get(&p.currency).is_none() {
+                anyhow::bail!("limits: payer currency {} is not in the currencies file", p.currency);
+            }
+            if [p.daily, p.monthly, p.yearly].into_iter().flatten().any(|v| !v.is_finite() || v <= 0.0) {
+                anyhow::bail!("limits: payer limits must be positive");
+            }
+            if p.min_rate_inr.is_some_and(|r| !r.is_finite() || r <= 0.0) {
+                anyhow::bail!("limits: payer min_rate_inr must be positive");
+            }
+        }
+        Ok(limits)
+    }
+
+    /// Whether the quote's source amount is within its currency's and its
+    /// corridor's limits.
+    pub fn check_quote(&self, quote: &Quote, currencies: &Registry) -> Result<(), LimitError> {
+        currencies.check_amount(&quote.source_currency, quote.source_amount).map_err(LimitError::Currency)?;
+        let corridor = format!("{}>{}", quote.source_currency, quote.dest_currency);
+        let Some(limit) = self.corridors.iter().find(|c| c.corridor == corridor) else { return Ok(()) };
+        let format = |v: f64| currencies.get(&quote.source_currency).map(|c| c.format(v)).unwrap_or_else(|| format!("{:.2}", v));
+        if let Some(min) = limit.min_amount.filter(|min| quote.source_amount < *min) {
+            return Err(LimitError::BelowMinimum { source_currency: quote.source_currency.clone(), dest_currency: quote.dest_currency.clone(), min: format(min) });
+        }
+        if let Some(max) = limit.max_amount.filter(|max| quote.source_amount > *max) {
+            return Err(LimitError::AboveMaximum { source_currency: quote.source_currency.clone(), dest_currency: quote.dest_currency.clone(), max: format(max) });
+        }
+        Ok(())
+    }
+}
+
+/// Start of each window ending at `now`, in `Window::ALL` order.
+pub fn window_starts(now: DateTime<Utc>) -> [DateTime<Utc>; 3] {
+    Window::ALL.map(|w| now - w.duration())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// LRS-style caps in USD, at ₹83 per USD.
+    fn usd_caps() -> PayerLimits {
+        PayerLimits { currency: "USD".into(), daily: Some(10_000.0), monthly: Some(50_000.0), yearly: Some(250_000.0), min_rate_inr: Some(80.0) }
+    }
+
+    const USD_INR: f64 = 83.0;
+
+    #[test]
+    fn payments_within_every_window_pass() {
+        assert!(usd_caps().check(83_000.0, [0.0, 0.0, 0.0], USD_INR).is_ok());
+        // Exactly reaching a cap is allowed
+        assert!(usd_caps().check(9_000.0 * USD_INR, [1_000.0 * USD_INR, 1_000.0 * USD_INR, 1_000.0 * USD_INR], USD_INR).is_ok());
+    }
+
+    #[test]
+    fn the_first_window_broken_is_reported_with_what_is_left() {
+        let err = usd_caps().check(3_000.0 * USD_INR, [8_000.0 * USD_INR, 8_000.0 * USD_INR, 8_000.0 * USD_INR], USD_INR).unwrap_err();
+        assert_eq!(err.code(), "payer_daily_limit");
+        match err {
+            LimitError::Payer { limit, used, remaining, currency, .. } => {
+                assert_eq!(currency, "USD");
+                assert_eq!(limit, 10_000.0);
+                assert!((used - 8_000.0).abs() < 1e-9);
+                assert!((remaining - 2_000.0).abs() < 1e-9);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        let err = usd_caps().check(5_000.0 * USD_INR, [0.0, 48_000.0 * USD_INR, 48_000.0 * USD_INR], USD_INR).unwrap_err();
+        assert_eq!(err.code(), "payer_monthly_limit");
+        let err = usd_caps().check(5_000.0 * USD_INR, [0.0, 0.0, 249_000.0 * USD_INR], USD_INR).unwrap_err();
+        assert_eq!(err.code(), "payer_yearly_limit");
+    }
+
+    #[test]
+    fn remaining_never_goes_negative() {
+        let caps = PayerLimits { currency: "USD".into(), daily: Some(100.0), monthly: None, yearly: None, min_rate_inr: None };
+        match caps.check(USD_INR, [200.0 * USD_INR, 0.0, 0.0], USD_INR) {
+            Err(LimitError::Payer { remaining, .. }) => assert_eq!(remaining, 0.0),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unset_windows_are_not_capped() {
+        let caps = PayerLimits { currency: "INR".into(), daily: None, monthly: None, yearly: Some(1_000_000.0), min_rate_inr: None };
+        assert!(caps.check(900_000.0, [5_000_000.0, 5_000_000.0, 0.0], 1.0).is_ok());
+    }
+
+    #[test]
+    fn windows_end_now() {
+        let now: DateTime<Utc> = "2026-10-19T06:00:00Z".parse().unwrap();
+        let [day, month, year] = window_starts(now);
+        assert_eq!(now - day, Duration::hours(24));
+        assert_eq!(now - month, Duration::days(30));
+        assert_eq!(now - year, Duration::days(365));
+    }
+
+    #[test]
+    fn payers_are_counted_by_device_and_ip() {
+        assert_eq!(Payer::all(Some("ab12"), Some("203.0.113.7")), [Payer::Device("ab12"), Payer::Ip("203.0.113.7")]);
+        assert_eq!(Payer::all(None, Some("203.0.113.7")), [Payer::Ip("203.0.113.7")]);
+        assert_eq!(Payer::all(None, None), []);
+    }
+
+    #[test]
+    fn the_heavier_key_counts_per_window() {
+        assert_eq!(heaviest(&[[10.0, 50.0, 90.0], [20.0, 30.0, 90.0]]), [20.0, 50.0, 90.0]);
+        assert_eq!(heaviest(&[]), [0.0; 3]);
+    }
+
+    #[test]
+    fn a_payment_under_the_caps_at_the_floor_is_under_them_at_higher_rates() {
+        let caps = usd_caps();
+        let used = [8_000.0 * USD_INR, 0.0, 0.0];
+        assert!(caps.check(1_500.0 * 80.0, used, 80.0).is_ok());
+        assert!(caps.check(1_500.0 * 80.0, used, USD_INR).is_ok());
+        // Refused at the floor but within the caps at the live rate
+        assert!(caps.check(2_000.0 * USD_INR, used, 80.0).is_err());
+        assert!(caps.check(2_000.0 * USD_INR, used, USD_INR).is_ok());
+    }
+}
@@ -1,9 +1,33 @@
 mod routes;
 mod db;
 mod ai;
+mod admin;
+mod backtest;
+mod clock;
+mod client;
+mod geoip;
+mod rails;
+mod kb;
+mod explain;
+mod assistant;
+mod lang;
+mod followup;
+mod fx;
+mod pricing;
+mod optimizer;
+mod notify;
+mod alerts;
+mod currency;
+mod limits;
+mod calendar;
+mod payee;
+mod validation;
+mod error;
+mod throttle;
 
 use axum::{Router};
 use std::net::{SocketAddr, IpAddr, Ipv4Addr};
+use std::sync::{Arc, RwLock};
 use tera::Tera;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -11,6 +35,33 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 pub struct AppState {
     pub templates: Tera,
     pub db: db::Db,
+    pub clock: clock::SharedClock,
+    /// Candidate rules scored in shadow on every payment (SHADOW_RISK_RULES).
+    pub shadow_rules: Option<Arc<ai::RiskRules>>,
+    /// Offline GeoIP database (GEOIP_DB_PATH), if configured.
+    pub geoip: Option<Arc<geoip::GeoIp>>,
+    /// UPI and bank (IMPS/NEFT) rails; payees are routed by type.
+    pub rails: Arc<rails::Rails>,
+    /// Assistant knowledge bases per language, reloadable at runtime via /admin/kb/reload.
+    pub kb: Arc<RwLock<kb::Library>>,
+    /// Backend answering /ask (ASSISTANT_BACKEND).
+    pub assistant: Arc<dyn assistant::AssistantBackend>,
+    /// Cached exchange rates shared by quotes, the optimizer and payments.
+    pub rates: Arc<fx::RateService>,
+    /// Where rate alerts are delivered (NOTIFIER).
+    pub notifier: Arc<dyn notify::Notifier>,
+    /// Corridor matrix: which sources pay out in which currencies (CORRIDORS).
+    pub currencies: Arc<currency::Registry>,
+    /// Per-corridor and cumulative payer limits (LIMITS_FILE).
+    pub limits: Arc<limits::Limits>,
+    /// Business days and cut-offs for expected credit dates (CALENDAR_DIR).
+    pub calendar: Arc<calendar::Calendar>,
+    /// Parses payee fields; knows the UPI handles (UPI_HANDLES).
+    pub payees: Arc<payee::Parser>,
+    /// Payee lookups allowed per client IP per 10 minutes (PAYEE_LOOKUPS_PER_10_MIN).
+    pub payee_lookups: Arc<throttle::Throttle>,
+    /// Alert subscriptions allowed per client IP and per contact per hour (ALERT_SIGNUPS_PER_HOUR).
+    pub alert_signups: Arc<throttle::Throttle>,
 }
 
 #[tokio::main]
@@ -26,7 +77,55 @@ async fn main() -> anyhow::Result<()> {
     let db = db::Db::connect_from_env().await?;
     db.migrate().await?;
 
-    let state = AppState { templates, db };
+    // Subcommands: `globalpay backtest --candidate rules.json ...`
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("backtest") {
+        let bt = backtest::BacktestArgs::parse(&args[1..])?;
+        return backtest::run(&db, bt).await;
+    }
+
+    let clock = clock::from_env()?;
+    let payees = Arc::new(payee::Parser::from_env()?);
+    let shadow_rules = match std::env::var("SHADOW_RISK_RULES") {
+        Ok(path) if !path.trim().is_empty() => {
+            let rules = ai::RiskRules::from_file(path.trim(), &payees)?;
+            tracing::info!(version = %rules.version, "Shadow risk rules enabled");
+            Some(Arc::new(rules))
+        }
+        _ => None,
+    };
+    let geoip = geoip::from_env();
+    let kb_dir = kb::Library::dir_from_env();
+    let knowledge = kb::Library::load(&kb_dir).unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "Knowledge base not loaded; /ask falls back to canned answers");
+        kb::Library::empty()
+    });
+    tracing::info!(passages = ?knowledge.counts(), dir = %kb_dir, "Knowledge base indexed");
+    let kb = Arc::new(RwLock::new(knowledge));
+    let assistant = assistant::from_env(kb.clone())?;
+    tracing::info!(backend = assistant.name(), "Assistant backend ready");
+    let rates = Arc::new(fx::RateService::from_env(db.clone())?);
+    let notifier = notify::from_env()?;
+    tracing::info!(notifier = notifier.name(), "Notifier ready");
+    alerts::AlertJob {
+        db: db.clone(),
+        rates: rates.clone(),
+        notifier: notifier.clone(),
+        clock: clock.clone(),
+        poll: alerts::AlertJob::poll_from_env(),
+    }
+    .spawn();
+    let currencies = Arc::new(currency::Registry::from_env()?);
+    let limits = Arc::new(limits::Limits::from_env(&currencies)?);
+    let calendar = Arc::new(calendar::Calendar::from_env(&currencies)?);
+    calendar.warn_if_expiring(clock.now().date_naive());
+    let rails = Arc::new(rails::Rails::from_env(&calendar)?);
+    if let Some(dest) = currencies.destinations().into_iter().find(|d| !rails.pays_out(&d.code)) {
+        anyhow::bail!("corridors pay out in {}, but no payment rail credits {}", dest.code, dest.code);
+    }
+    let payee_lookups = Arc::new(throttle::Throttle::from_env("PAYEE_LOOKUPS_PER_10_MIN", 30, chrono::Duration::minutes(10))?);
+    let alert_signups = Arc::new(throttle::Throttle::from_env("ALERT_SIGNUPS_PER_HOUR", 5, chrono::Duration::hours(1))?);
+    let state = AppState { templates, db, clock, shadow_rules, geoip, rails, kb, assistant, rates, notifier, currencies, limits, calendar, payees, payee_lookups, alert_signups };
 
     let app: Router = routes::router(state);
 
@@ -37,7 +136,7 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("Open desktop QR page: {}/", public_base);
     tracing::info!("Mobile will open: {}/pay?sid=<session>", public_base);
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
     Ok(())
 }
 
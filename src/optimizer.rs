@@ -0,0 +1,255 @@
+use serde::Serialize;
+
+use crate::fx::Rate;
+use crate::pricing::{round2, Precision, Quote};
+
+// Cheapest way to deliver a target INR amount from the balances a payer holds
+// ("500 AED, 100 EUR"). Every currency used is its own payment (a leg) with
+// its own fees, so splitting only pays off when no single balance covers the
+// target. Plans are compared by what they take out of the payer's balances,
+// valued in a reference currency of their choice at the same cached rates
+// payments use.
+
+/// Most currencies one plan may combine.
+pub const MAX_LEGS: usize = 3;
+/// Most declared balances; plans are searched over every subset.
+pub const MAX_BALANCES: usize = 10;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Balance {
+    pub currency: String,
+    pub amount: f64,
+}
+
+/// Parse declared balances: "500 AED, 100 EUR" or "AED:500,EUR:100".
+/// Repeated currencies are added up.
+pub fn parse_balances(input: &str) -> Result<Vec<Balance>, String> {
+    let mut out: Vec<Balance> = Vec::new();
+    for part in input.split([',', ';']).map(str::trim).filter(|p| !p.is_empty()) {
+        let words: Vec<&str> = part.split([':', '=', ' ']).filter(|w| !w.is_empty()).collect();
+        let parsed = match words.as_slice() {
+            [a, b] => match (a.parse::<f64>(), b.parse::<f64>()) {
+                (Ok(n), Err(_)) => Some((*b, n)),
+                (Err(_), Ok(n)) => Some((*a, n)),
+                _ => None,
+            },
+            _ => None,
+        };
+        let (code, amount) = parsed.ok_or_else(|| format!("Could not read balance \"{}\"; use e.g. \"500 AED\"", part))?;
+        let code = code.to_uppercase();
+        if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(format!("\"{}\" is not a currency code", code));
+        }
+        if !amount.is_finite() || amount < 0.0 {
+            return Err(format!("Balance for {} must be a positive amount", code));
+        }
+        match out.iter_mut().find(|b| b.currency == code) {
+            Some(b) => b.amount += amount,
+            None => out.push(Balance { currency: code, amount }),
+        }
+    }
+    if out.len() > MAX_BALANCES {
+        return Err(format!("At most {} balances", MAX_BALANCES));
+    }
+    Ok(out)
+}
+
+/// A currency the payer can pay from, with its balance (None: unlimited).
+#[derive(Debug, Clone)]
+pub struct Holding {
+    pub balance: Option<f64>,
+    pub rate: Rate,
+    pub precision: Precision,
+}
+
+impl Holding {
+    /// Most INR this holding can deliver in one payment, after its fees.
+    pub fn capacity_inr(&self) -> f64 {
+        let Some(balance) = self.balance else { return f64::INFINITY };
+        let fee_src = Quote::forward(0.0, &self.rate, self.precision).fee_src;
+        let sendable = balance - fee_src;
+        if sendable <= 0.0 {
+            return 0.0;
+        }
+        // Reverse quotes round the source amount up, so step down until one fits
+        let mut cap = ((sendable * self.rate.rate * 100.0) + 1e-6).floor() / 100.0;
+        while cap > 0.0 && Quote::reverse(cap, &self.rate, self.precision).total_src > balance + 1e-9 {
+            cap = round2(cap - 0.01);
+        }
+        cap.max(0.0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Leg {
+    pub balance: Option<f64>,
+    #[serde(flatten)]
+    pub quote: Quote,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Plan {
+    pub legs: Vec<Leg>,
+    /// Receiver credit across all legs.
+    pub amount_inr: f64,
+    pub fee_inr: f64,
+    pub total_inr: f64,
+    pub reference: String,
+    /// What the plan takes out of the payer's balances, in `reference`.
+    pub cost_ref: f64,
+    /// The fee part of `cost_ref`.
+    pub fee_ref: f64,
+}
+
+/// Every plan that delivers exactly `target_inr` using at most `MAX_LEGS`
+/// holdings, cheapest first. Plans with a leg that isn't needed are left out.
+/// At most `MAX_BALANCES` holdings may have a balance; holdings without one
+/// cover any target alone, so any number of them is fine.
+pub fn plans(target_inr: f64, holdings: &[Holding], reference: &Rate) -> Result<Vec<Plan>, String> {
+    let target_inr = round2(target_inr);
+    let (limited, unlimited): (Vec<&Holding>, Vec<&Holding>) = holdings.iter().partition(|h| h.balance.is_some());
+    if limited.len() > MAX_BALANCES {
+        return Err(format!("At most {} balances", MAX_BALANCES));
+    }
+    let mut out: Vec<Plan> = unlimited.iter().filter_map(|h| fill(target_inr, &[*h], reference)).collect();
+    for mask in 1u32..(1 << limited.len()) {
+        if mask.count_ones() as usize > MAX_LEGS {
+            continue;
+        }
+        let mut subset: Vec<&Holding> = (0..limited.len()).filter(|i| mask & (1 << i) != 0).map(|i| limited[i]).collect();
+        // Fee-free INR first, then the largest balances, so later legs only top up
+        subset.sort_by(|a, b| (b.rate.base == "INR").cmp(&(a.rate.base == "INR")).then_with(|| b.capacity_inr().total_cmp(&a.capacity_inr())));
+        if let Some(plan) = fill(target_inr, &subset, reference) {
+            out.push(plan);
+        }
+    }
+    out.sort_by(|a, b| {
+        a.cost_ref
+            .total_cmp(&b.cost_ref)
+            .then_with(|| a.legs.len().cmp(&b.legs.len()))
+            .then_with(|| a.legs[0].quote.source_currency.cmp(&b.legs[0].quote.source_currency))
+    });
+    Ok(out)
+}
+
+fn fill(target_inr: f64, subset: &[&Holding], reference: &Rate) -> Option<Plan> {
+    let mut remaining = target_inr;
+    let mut legs = Vec::new();
+    for h in subset {
+        if remaining < 0.01 {
+            return None;
+        }
+        let take = round2(remaining.min(h.capacity_inr()));
+        if take < 0.01 {
+            return None;
+        }
+        legs.push(Leg { balance: h.balance, quote: Quote::reverse(take, &h.rate, h.precision) });
+        remaining = round2(remaining - take);
+    }
+    if remaining >= 0.01 {
+        return None;
+    }
+    let sum = |f: fn(&Quote) -> f64| round2(legs.iter().map(|l| f(&l.quote)).sum());
+    let cost_inr: f64 = legs.iter().map(|l| l.quote.total_src * l.quote.rate).sum();
+    let fee_inr = sum(|q| q.fee_inr);
+    Some(Plan {
+        amount_inr: sum(|q| q.amount_inr),
+        fee_inr,
+        total_inr: sum(|q| q.total_inr),
+        reference: reference.base.clone(),
+        cost_ref: round2(cost_inr / reference.rate),
+        fee_ref: round2(fee_inr / reference.rate),
+        legs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+
+    fn rate(base: &str, to_inr: f64) -> Rate {
+        let fetched_at = "2026-10-19T06:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        Rate { base: base.into(), quote: "INR".into(), rate: to_inr, quote_inr: 1.0, fetched_at: Some(fetched_at), provider: "test".into() }
+    }
+
+    fn holding(base: &str, to_inr: f64, balance: Option<f64>) -> Holding {
+        Holding { balance, rate: rate(base, to_inr), precision: Precision::default() }
+    }
+
+    #[test]
+    fn parse_balances_accepts_both_orders_and_adds_repeats() {
+        let b = parse_balances("500 AED, EUR:100; aed=20").unwrap();
+        assert_eq!(b, vec![Balance { currency: "AED".into(), amount: 520.0 }, Balance { currency: "EUR".into(), amount: 100.0 }]);
+        assert!(parse_balances("500 dirhams").is_err());
+        assert!(parse_balances("-5 AED").is_err());
+    }
+
+    #[test]
+    fn capacity_is_the_largest_credit_the_balance_pays_for() {
+        let aed = holding("AED", 22.6004, Some(100.0));
+        let cap = aed.capacity_inr();
+        assert!(cap > 2_100.0);
+        assert!(Quote::reverse(cap, &aed.rate, aed.precision).total_src <= 100.0);
+        assert!(Quote::reverse(cap + 0.01, &aed.rate, aed.precision).total_src > 100.0);
+    }
+
+    #[test]
+    fn capacity_edge_cases() {
+        assert_eq!(holding("AED", 22.6004, None).capacity_inr(), f64::INFINITY);
+        // Not even the fees are covered
+        assert_eq!(holding("AED", 22.6004, Some(5.0)).capacity_inr(), 0.0);
+        // INR pays no fees, so the whole balance goes through
+        assert_eq!(holding("INR", 1.0, Some(1_234.5)).capacity_inr(), 1_234.5);
+    }
+
+    #[test]
+    fn one_balance_that_covers_the_target_is_a_single_leg() {
+        let holdings = [holding("AED", 22.6004, Some(1_000.0)), holding("EUR", 90.0, Some(10.0))];
+        let plans = plans(5_000.0, &holdings, &rate("INR", 1.0)).unwrap();
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].legs.len(), 1);
+        assert_eq!(plans[0].legs[0].quote.source_currency, "AED");
+        assert_eq!(plans[0].amount_inr, 5_000.0);
+    }
+
+    #[test]
+    fn balances_are_split_when_none_covers_the_target() {
+        let holdings = [holding("AED", 22.6004, Some(100.0)), holding("EUR", 90.0, Some(100.0))];
+        let plans = plans(10_000.0, &holdings, &rate("EUR", 90.0)).unwrap();
+        assert_eq!(plans.len(), 1);
+        let plan = &plans[0];
+        assert_eq!(plan.legs.len(), 2);
+        // The larger balance goes first and the other only tops up
+        assert_eq!(plan.legs[0].quote.source_currency, "EUR");
+        assert_eq!(plan.amount_inr, 10_000.0);
+        assert_eq!(plan.reference, "EUR");
+        assert!(plan.legs.iter().all(|l| l.quote.total_src <= l.balance.unwrap()));
+    }
+
+    #[test]
+    fn plans_are_cheapest_first_in_the_reference_currency() {
+        // INR is fee-free, so it beats AED at the same rate
+        let holdings = [holding("AED", 22.6004, None), holding("INR", 1.0, None)];
+        let plans = plans(1_000.0, &holdings, &rate("AED", 22.6004)).unwrap();
+        assert_eq!(plans.iter().map(|p| p.legs[0].quote.source_currency.as_str()).collect::<Vec<_>>(), ["INR", "AED"]);
+        assert!(plans[0].cost_ref < plans[1].cost_ref);
+        assert_eq!(plans[0].fee_ref, 0.0);
+    }
+
+    #[test]
+    fn no_plan_when_balances_fall_short() {
+        let holdings = [holding("AED", 22.6004, Some(10.0))];
+        assert!(plans(10_000.0, &holdings, &rate("INR", 1.0)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn too_many_balances_are_rejected_not_truncated() {
+        let codes = ["AAA", "BBB", "CCC", "DDD", "EEE", "FFF", "GGG", "HHH", "III", "JJJ", "KKK"];
+        let limited: Vec<Holding> = codes.iter().map(|c| holding(c, 10.0, Some(100.0))).collect();
+        assert!(plans(100.0, &limited, &rate("INR", 1.0)).is_err());
+        // Without balances every currency gets its single-leg plan
+        let unlimited: Vec<Holding> = codes.iter().map(|c| holding(c, 10.0, None)).collect();
+        assert_eq!(plans(100.0, &unlimited, &rate("INR", 1.0)).unwrap().len(), codes.len());
+    }
+}
@@ -0,0 +1,329 @@
+use std::collections::BTreeSet;
+
+// Who a payment goes to, parsed from what the payer typed into the payee
+// field. A payee is one of:
+// - a UPI virtual payment address (`name@handle`), whose handle must be a
+//   PSP handle we know;
+// - an Indian mobile number, with or without +91 / 0091 / 0, paid through
+//   the UPI number mapper;
+// - an international mobile number in E.164 form (+971...), for NRI accounts
+//   on UPI international, from the countries NPCI has opened;
+// - a bank account number and IFSC, paid to the account's NPCI address
+//   (`<account>@<ifsc>.ifsc.npci`).
+// UPI_HANDLES ("okaxis,fam,...") adds handles to the built-in list.
+
+/// PSP handles UPI addresses can use.
+const HANDLES: &[&str] = &[
+    "upi", "oksbi", "okhdfcbank", "okicici", "okaxis", "okrbi", "ybl", "ibl", "axl", "paytm", "ptyes", "ptaxis", "pthdfc",
+    "ptsbi", "apl", "yapl", "rapl", "sbi", "rbi", "axisbank", "hdfcbank", "icici", "kotak", "indus", "federal", "pnb",
+    "barodampay", "unionbank", "cnrb", "idfcbank", "yesbank", "aubank", "jupiteraxis", "fam", "freecharge", "airtel",
+    "jio", "waaxis", "wahdfcbank", "wasbi", "waicici",
+];
+
+/// Countries whose mobile numbers can hold UPI accounts (NRE/NRO), by
+/// calling code.
+const INTERNATIONAL: &[(&str, &str)] = &[
+    ("1", "USA/Canada"),
+    ("44", "UK"),
+    ("61", "Australia"),
+    ("65", "Singapore"),
+    ("852", "Hong Kong"),
+    ("966", "Saudi Arabia"),
+    ("968", "Oman"),
+    ("971", "UAE"),
+    ("974", "Qatar"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Payee {
+    /// Lowercased, e.g. "priya.sharma@oksbi".
+    Vpa(String),
+    /// Ten digits without a prefix, e.g. "9876543210".
+    Mobile(String),
+    /// E.164, e.g. "+971501234567".
+    International { e164: String, country: &'static str },
+    /// IFSC uppercased, e.g. "SBIN0001234".
+    Account { number: String, ifsc: String },
+}
+
+impl Payee {
+    /// The address payments are made and looked up to, as stored in
+    /// `payments.upi_id`.
+    pub fn address(&self) -> String {
+        match self {
+            Payee::Vpa(vpa) => vpa.clone(),
+            Payee::Mobile(number) => format!("{}@upi", number),
+            Payee::International { e164, .. } => format!("{}@upi", e164.trim_start_matches('+')),
+            Payee::Account { number, ifsc } => format!("{}@{}.ifsc.npci", number, ifsc.to_lowercase()),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Payee::Vpa(_) => "vpa",
+            Payee::Mobile(_) => "mobile",
+            Payee::International { .. } => "international_mobile",
+            Payee::Account { .. } => "account",
+        }
+    }
+
+    /// For the payer, e.g. "+91 98765 43210", "+971501234567 (UAE)" or "A/c ••••6789, SBIN0001234".
+    pub fn display(&self) -> String {
+        match self {
+            Payee::Vpa(vpa) => vpa.clone(),
+            Payee::Mobile(n) => format!("+91 {} {}", &n[..5], &n[5..]),
+            Payee::International { e164, country } => format!("{} ({})", e164, country),
+            Payee::Account { number, ifsc } => format!("A/c ••••{}, {}", &number[number.len() - 4..], ifsc),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum PayeeError {
+    #[error("Enter a UPI ID like name@bank, a mobile number, or an account number and IFSC")]
+    Empty,
+    #[error("{0}")]
+    Vpa(&'static str),
+    #[error("@{0} is not a UPI handle we recognise")]
+    UnknownHandle(String),
+    #[error("{0}")]
+    Mobile(&'static str),
+    #[error("UPI only reaches international numbers from the USA, Canada, the UK, Australia, Singapore, Hong Kong, Saudi Arabia, Oman, the UAE and Qatar")]
+    Country,
+    #[error("Account numbers are 9 to 18 digits")]
+    Account,
+    #[error("{0} is not a valid IFSC: it should be 4 letters, a 0 and 6 letters or digits, like SBIN0001234")]
+    Ifsc(String),
+}
+
+impl PayeeError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            PayeeError::Empty => "payee_required",
+            PayeeError::Vpa(_) => "vpa_invalid",
+            PayeeError::UnknownHandle(_) => "vpa_unknown_handle",
+            PayeeError::Mobile(_) => "mobile_invalid",
+            PayeeError::Country => "mobile_country_unsupported",
+            PayeeError::Account => "account_invalid",
+            PayeeError::Ifsc(_) => "ifsc_invalid",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Parser {
+    handles: BTreeSet<String>,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Parser { handles: HANDLES.iter().map(|h| h.to_string()).collect() }
+    }
+}
+
+impl Parser {
+    pub fn from_env() -> anyhow::Result<Self> {
+        let mut parser = Self::default();
+        if let Ok(raw) = std::env::var("UPI_HANDLES") {
+            for handle in raw.split(',').map(|h| h.trim().trim_start_matches('@').to_lowercase()).filter(|h| !h.is_empty()) {
+                if !valid_handle(&handle) {
+                    anyhow::bail!("UPI_HANDLES: {:?} is not a UPI handle", handle);
+                }
+                parser.handles.insert(handle);
+            }
+        }
+        Ok(parser)
+    }
+
+    /// The PSP handles UPI addresses may use, sorted.
+    pub fn handles(&self) -> impl Iterator<Item = &str> {
+        self.handles.iter().map(String::as_str)
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Payee, PayeeError> {
+        let s = input.trim();
+        if s.is_empty() {
+            return Err(PayeeError::Empty);
+        }
+        if s.contains('@') {
+            return self.vpa(s);
+        }
+        if s.chars().any(|c| c.is_ascii_alphabetic()) {
+            return account(s);
+        }
+        mobile(s)
+    }
+
+    fn vpa(&self, s: &str) -> Result<Payee, PayeeError> {
+        let vpa = s.to_lowercase();
+        let Some((name, handle)) = vpa.split_once('@') else { return Err(PayeeError::Vpa("UPI IDs look like name@bank")) };
+        if let Some(ifsc) = handle.strip_suffix(".ifsc.npci") {
+            return account_parts(name, ifsc);
+        }
+        if !(2..=256).contains(&name.len()) {
+            return Err(PayeeError::Vpa("The part before @ must be 2 to 256 characters"));
+        }
+        if !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_')) {
+            return Err(PayeeError::Vpa("UPI IDs can only use letters, digits, '.', '-' and '_' before the @"));
+        }
+        if name.starts_with('.') || name.ends_with('.') || name.contains("..") {
+            return Err(PayeeError::Vpa("UPI IDs can't start or end with '.' or have two in a row"));
+        }
+        if !valid_handle(handle) {
+            return Err(PayeeError::Vpa("The part after @ must be your bank or app's handle, like oksbi"));
+        }
+        if !self.handles.contains(handle) {
+            return Err(PayeeError::UnknownHandle(handle.to_string()));
+        }
+        Ok(Payee::Vpa(vpa))
+    }
+}
+
+/// Shape of a PSP handle: 2–64 letters and digits, starting with a letter.
+fn valid_handle(handle: &str) -> bool {
+    (2..=64).contains(&handle.len()) && handle.chars().all(|c| c.is_ascii_alphanumeric()) && handle.starts_with(|c: char| c.is_ascii_alphabetic())
+}
+
+/// "+91 98765 43210", "098765-43210", "+971 50 123 4567", "00971501234567".
+fn mobile(s: &str) -> Result<Payee, PayeeError> {
+    let compact: String = s.chars().filter(|c| !matches!(c, ' ' | '-' | '(' | ')' | '.')).collect();
+    let (international, digits) = match compact.strip_prefix('+') {
+        Some(rest) => (true, rest),
+        None => match compact.strip_prefix("00") {
+            Some(rest) => (true, rest),
+            None => (false, compact.as_str()),
+        },
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(PayeeError::Mobile("Mobile numbers can only contain digits, spaces and a leading +"));
+    }
+    let national = match (international, digits.len()) {
+        (true, 12) if digits.starts_with("91") => &digits[2..],
+        (true, _) if digits.starts_with("91") => return Err(PayeeError::Mobile("Indian mobile numbers have 10 digits after +91")),
+        (true, _) => return international_number(digits),
+        (false, 10) => digits,
+        (false, 11) if digits.starts_with('0') => &digits[1..],
+        (false, 12) if digits.starts_with("91") => &digits[2..],
+        (false, _) => return Err(PayeeError::Mobile("Enter a 10-digit mobile number, or an international one starting with +")),
+    };
+    if !national.starts_with(['6', '7', '8', '9']) {
+        return Err(PayeeError::Mobile("Indian mobile numbers start with 6, 7, 8 or 9"));
+    }
+    Ok(Payee::Mobile(national.to_string()))
+}
+
+fn international_number(digits: &str) -> Result<Payee, PayeeError> {
+    // E.164 numbers are at most 15 digits, country code included
+    if !(8..=15).contains(&digits.len()) || digits.starts_with('0') {
+        return Err(PayeeError::Mobile("International numbers are 8 to 15 digits including the country code"));
+    }
+    match INTERNATIONAL.iter().find(|(code, _)| digits.starts_with(*code)) {
+        Some((_, country)) => Ok(Payee::International { e164: format!("+{}", digits), country }),
+        None => Err(PayeeError::Country),
+    }
+}
+
+/// "50100123456789 HDFC0001234", "HDFC0001234 / 50100123456789".
+fn account(s: &str) -> Result<Payee, PayeeError> {
+    let tokens: Vec<&str> = s.split(|c: char| c.is_whitespace() || matches!(c, '/' | ',' | ';' | ':')).filter(|t| !t.is_empty()).collect();
+    let (codes, numbers): (Vec<&str>, Vec<&str>) = tokens.into_iter().partition(|t| t.chars().any(|c| c.is_ascii_alphabetic()));
+    match (codes.as_slice(), numbers.as_slice()) {
+        ([ifsc], [_, ..]) => account_parts(&numbers.concat(), ifsc),
+        ([ifsc], []) => Err(if is_ifsc(&ifsc.to_uppercase()) { PayeeError::Account } else { PayeeError::Ifsc(ifsc.to_uppercase()) }),
+        _ => Err(PayeeError::Vpa("Enter a UPI ID like name@bank, a mobile number, or an account number and IFSC")),
+    }
+}
+
+fn account_parts(number: &str, ifsc: &str) -> Result<Payee, PayeeError> {
+    let ifsc = ifsc.to_uppercase();
+    if !is_ifsc(&ifsc) {
+        return Err(PayeeError::Ifsc(ifsc));
+    }
+    if !(9..=18).contains(&number.len()) || !number.chars().all(|c| c.is_ascii_digit()) {
+        return Err(PayeeError::Account);
+    }
+    Ok(Payee::Account { number: number.to_string(), ifsc })
+}
+
+/// IFSC: a 4-letter bank code, a 0 (reserved) and a 6-character branch code.
+/// IFSC has no check digit, so the reserved 0 is the only internal check.
+pub fn is_ifsc(code: &str) -> bool {
+    let b = code.as_bytes();
+    b.len() == 11 && b[..4].iter().all(u8::is_ascii_uppercase) && b[4] == b'0' && b[5..].iter().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Result<Payee, PayeeError> {
+        Parser::default().parse(input)
+    }
+
+    #[test]
+    fn vpas_are_lowercased_and_need_a_known_handle() {
+        assert_eq!(parse(" Priya.Sharma@OKSBI "), Ok(Payee::Vpa("priya.sharma@oksbi".into())));
+        assert_eq!(parse("priya@unknownbank"), Err(PayeeError::UnknownHandle("unknownbank".into())));
+        assert!(matches!(parse("p@oksbi"), Err(PayeeError::Vpa(_))));
+        assert!(matches!(parse("pri..ya@oksbi"), Err(PayeeError::Vpa(_))));
+        assert!(matches!(parse("priya!@oksbi"), Err(PayeeError::Vpa(_))));
+        assert!(matches!(parse("priya@1bank"), Err(PayeeError::Vpa(_))));
+    }
+
+    #[test]
+    fn extra_handles_are_accepted() {
+        let mut parser = Parser::default();
+        parser.handles.insert("newpsp".into());
+        assert_eq!(parser.parse("priya@newpsp"), Ok(Payee::Vpa("priya@newpsp".into())));
+        assert!(parser.handles().any(|h| h == "newpsp"));
+    }
+
+    #[test]
+    fn indian_mobiles_in_any_common_form() {
+        for input in ["9876543210", "+91 98765 43210", "0091-98765-43210", "098765 43210", "919876543210"] {
+            assert_eq!(parse(input), Ok(Payee::Mobile("9876543210".into())), "{}", input);
+        }
+        assert!(matches!(parse("5876543210"), Err(PayeeError::Mobile(_))));
+        assert!(matches!(parse("+91 98765 4321"), Err(PayeeError::Mobile(_))));
+        assert!(matches!(parse("98765"), Err(PayeeError::Mobile(_))));
+    }
+
+    #[test]
+    fn international_mobiles_from_upi_countries_only() {
+        let uae = parse("+971 50 123 4567").unwrap();
+        assert_eq!(uae, Payee::International { e164: "+971501234567".into(), country: "UAE" });
+        assert_eq!(uae.address(), "971501234567@upi");
+        assert_eq!(parse("+33 6 12 34 56 78"), Err(PayeeError::Country));
+        assert!(matches!(parse("+971 50"), Err(PayeeError::Mobile(_))));
+    }
+
+    #[test]
+    fn accounts_with_ifsc_either_way_round() {
+        let expected = Payee::Account { number: "50100123456789".into(), ifsc: "HDFC0001234".into() };
+        assert_eq!(parse("50100123456789 HDFC0001234"), Ok(expected.clone()));
+        assert_eq!(parse("hdfc0001234 / 50100123456789"), Ok(expected.clone()));
+        assert_eq!(parse("50100123456789@hdfc0001234.ifsc.npci"), Ok(expected.clone()));
+        assert_eq!(expected.address(), "50100123456789@hdfc0001234.ifsc.npci");
+        assert_eq!(expected.display(), "A/c ••••6789, HDFC0001234");
+        assert_eq!(parse("HDFC0001234"), Err(PayeeError::Account));
+        assert_eq!(parse("12345 HDFC0001234"), Err(PayeeError::Account));
+        assert_eq!(parse("50100123456789 HDFC1001234"), Err(PayeeError::Ifsc("HDFC1001234".into())));
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(parse("   "), Err(PayeeError::Empty));
+    }
+
+    #[test]
+    fn ifsc_shape() {
+        assert!(is_ifsc("SBIN0001234"));
+        assert!(is_ifsc("HDFC0ABC123"));
+        assert!(!is_ifsc("sbin0001234"));
+        assert!(!is_ifsc("SBIN1001234"));
+        assert!(!is_ifsc("SBI00001234"));
+        assert!(!is_ifsc("SBIN000123"));
+        assert!(!is_ifsc("SBIN0001234X"));
+        assert!(!is_ifsc("SBIN000-234"));
+    }
+}
@@ -0,0 +1,219 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::fx::Rate;
+
+// Pricing shared by the quote API, the optimizer and real payments. The
+// receiver gets source_amount × rate in the destination currency; cross-border
+// payments add a fixed transfer fee and platform fee (set in INR) on top,
+// converted to the source currency at the same rates. Same-currency payments
+// are free.
+//
+// Quotes run either way: forward from what the payer sends, or reverse from
+// what the receiver should get ("send ₹10,000").
+
+pub const TRANSFER_FEE_INR: f64 = 99.0;
+pub const PLATFORM_FEE_INR: f64 = 25.0;
+
+pub fn round2(v: f64) -> f64 {
+    round_minor(v, 2)
+}
+
+/// Round to `minor` decimal places (a currency's minor units).
+pub fn round_minor(v: f64, minor: u32) -> f64 {
+    let scale = 10f64.powi(minor as i32);
+    (v * scale).round() / scale
+}
+
+/// Round up to `minor` decimal places, ignoring float noise below them.
+pub fn ceil_minor(v: f64, minor: u32) -> f64 {
+    let scale = 10f64.powi(minor as i32);
+    ((v * scale) - 1e-6).ceil() / scale
+}
+
+/// Minor units of a quote's source and destination currencies.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Precision {
+    pub source: u32,
+    pub dest: u32,
+}
+
+impl Default for Precision {
+    fn default() -> Self {
+        Precision { source: 2, dest: 2 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuoteMode {
+    /// Priced from the source amount.
+    Forward,
+    /// Priced from the receiver's credit.
+    Reverse,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Quote {
+    pub mode: QuoteMode,
+    pub source_currency: String,
+    pub source_amount: f64,
+    pub dest_currency: String,
+    /// Receiver credit, in `dest_currency`.
+    pub dest_amount: f64,
+    /// Units of `dest_currency` per unit of `source_currency`.
+    pub rate: f64,
+    pub rate_provider: String,
+    pub rate_timestamp: Option<DateTime<Utc>>,
+    /// Receiver credit valued in INR (equal to `dest_amount` for INR payouts).
+    pub amount_inr: f64,
+    pub fee_transfer_inr: f64,
+    pub fee_platform_inr: f64,
+    pub fee_inr: f64,
+    pub fee_src: f64,
+    pub total_inr: f64,
+    pub total_src: f64,
+}
+
+impl Quote {
+    /// Price `source_amount` of `rate.base` paid out in `rate.quote`.
+    pub fn forward(source_amount: f64, rate: &Rate, precision: Precision) -> Self {
+        let source_amount = round_minor(source_amount, precision.source);
+        Self::build(QuoteMode::Forward, source_amount, round_minor(source_amount * rate.rate, precision.dest), rate, precision)
+    }
+
+    /// The source amount needed so the receiver is credited exactly
+    /// `dest_amount` of `rate.quote`. The source amount is rounded up to the
+    /// source currency's minor unit, so the payer never sends less than the
+    /// credit is worth.
+    pub fn reverse(dest_amount: f64, rate: &Rate, precision: Precision) -> Self {
+        let dest_amount = round_minor(dest_amount, precision.dest);
+        let source_amount = if rate.base == rate.quote { dest_amount } else { ceil_minor(dest_amount / rate.rate, precision.source) };
+        Self::build(QuoteMode::Reverse, source_amount, dest_amount, rate, precision)
+    }
+
+    fn build(mode: QuoteMode, source_amount: f64, dest_amount: f64, rate: &Rate, precision: Precision) -> Self {
+        // Same-currency payouts are free; fees are set in INR and converted at
+        // the source currency's INR rate
+        let domestic = rate.base == rate.quote;
+        let (fee_transfer_inr, fee_platform_inr) = if domestic { (0.0, 0.0) } else { (TRANSFER_FEE_INR, PLATFORM_FEE_INR) };
+        let fee_inr = fee_transfer_inr + fee_platform_inr;
+        let fee_src = if domestic { 0.0 } else { round_minor(fee_inr / (rate.rate * rate.quote_inr), precision.source) };
+        let amount_inr = round2(dest_amount * rate.quote_inr);
+        Quote {
+            mode,
+            source_currency: rate.base.clone(),
+            source_amount,
+            dest_currency: rate.quote.clone(),
+            dest_amount,
+            rate: rate.rate,
+            rate_provider: rate.provider.clone(),
+            rate_timestamp: rate.fetched_at,
+            amount_inr,
+            fee_transfer_inr,
+            fee_platform_inr,
+            fee_inr,
+            fee_src,
+            total_inr: round2(amount_inr + fee_inr),
+            total_src: round_minor(source_amount + fee_src, precision.source),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate(base: &str, quote: &str, rate: f64, quote_inr: f64) -> Rate {
+        let fetched_at = "2026-10-19T06:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        Rate { base: base.into(), quote: quote.into(), rate, quote_inr, fetched_at: Some(fetched_at), provider: "test".into() }
+    }
+
+    #[test]
+    fn ceil_minor_rounds_up_but_ignores_float_noise() {
+        assert_eq!(ceil_minor(442.4700447, 2), 442.48);
+        assert_eq!(ceil_minor(1.1, 2), 1.1);
+        assert_eq!(ceil_minor(0.1 + 0.2, 2), 0.3);
+        assert_eq!(ceil_minor(1799.2, 0), 1800.0);
+        assert_eq!(ceil_minor(2.0, 3), 2.0);
+    }
+
+    #[test]
+    fn forward_converts_at_the_live_rate_and_adds_fees_in_the_source_currency() {
+        let aed = rate("AED", "INR", 22.6004, 1.0);
+        let q = Quote::forward(100.0, &aed, Precision::default());
+        assert_eq!(q.mode, QuoteMode::Forward);
+        assert_eq!(q.dest_amount, 2_260.04);
+        assert_eq!(q.amount_inr, 2_260.04);
+        assert_eq!(q.fee_transfer_inr, TRANSFER_FEE_INR);
+        assert_eq!(q.fee_platform_inr, PLATFORM_FEE_INR);
+        assert_eq!(q.fee_src, 5.49);
+        assert_eq!(q.total_src, 105.49);
+        assert_eq!(q.total_inr, 2_384.04);
+        assert_eq!(q.rate_provider, "test");
+    }
+
+    #[test]
+    fn inr_payments_are_free() {
+        let inr = rate("INR", "INR", 1.0, 1.0);
+        let q = Quote::forward(1_000.0, &inr, Precision::default());
+        assert_eq!(q.dest_amount, 1_000.0);
+        assert_eq!(q.fee_inr, 0.0);
+        assert_eq!(q.fee_src, 0.0);
+        assert_eq!(q.total_src, 1_000.0);
+        assert_eq!(q.total_inr, 1_000.0);
+    }
+
+    #[test]
+    fn reverse_rounds_the_source_up_so_the_receiver_gets_the_full_amount() {
+        let aed = rate("AED", "INR", 22.6004, 1.0);
+        let q = Quote::reverse(10_000.0, &aed, Precision::default());
+        assert_eq!(q.mode, QuoteMode::Reverse);
+        assert_eq!(q.dest_amount, 10_000.0);
+        assert_eq!(q.source_amount, 442.48);
+        assert!(q.source_amount * aed.rate >= q.dest_amount);
+        assert_eq!(q.fee_inr, TRANSFER_FEE_INR + PLATFORM_FEE_INR);
+        assert_eq!(q.fee_src, 5.49);
+        assert_eq!(q.total_src, 447.97);
+        assert_eq!(q.total_inr, 10_124.0);
+    }
+
+    #[test]
+    fn reverse_respects_source_minor_units() {
+        // A zero-decimal source currency pays whole units, rounded up
+        let jpy = rate("JPY", "INR", 0.5555, 1.0);
+        let q = Quote::reverse(1_000.0, &jpy, Precision { source: 0, dest: 2 });
+        assert_eq!(q.source_amount, 1801.0);
+        assert_eq!(q.source_amount.fract(), 0.0);
+        assert_eq!(q.total_src.fract(), 0.0);
+    }
+
+    #[test]
+    fn reverse_values_non_inr_payouts_in_inr() {
+        // AED paying out NPR: 1 AED = 36.2 NPR, 1 NPR = 0.625 INR
+        let cross = rate("AED", "NPR", 36.2, 0.625);
+        let q = Quote::reverse(3_620.0, &cross, Precision::default());
+        assert_eq!(q.source_amount, 100.0);
+        assert_eq!(q.amount_inr, 2_262.5);
+        assert_eq!(q.fee_src, round2(124.0 / (36.2 * 0.625)));
+    }
+
+    #[test]
+    fn same_currency_reverse_is_exact_and_free() {
+        let inr = rate("INR", "INR", 1.0, 1.0);
+        let q = Quote::reverse(2_500.5, &inr, Precision::default());
+        assert_eq!(q.source_amount, 2_500.5);
+        assert_eq!(q.dest_amount, 2_500.5);
+        assert_eq!(q.fee_inr, 0.0);
+        assert_eq!(q.total_src, q.source_amount);
+    }
+
+    #[test]
+    fn forward_and_reverse_agree() {
+        let aed = rate("AED", "INR", 22.6004, 1.0);
+        let forward = Quote::forward(100.0, &aed, Precision::default());
+        let reverse = Quote::reverse(forward.dest_amount, &aed, Precision::default());
+        assert_eq!(reverse.source_amount, 100.0);
+        assert_eq!(reverse.total_src, forward.total_src);
+    }
+}
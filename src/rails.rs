@@ -0,0 +1,399 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveTime, Timelike, Utc};
+use chrono_tz::{Asia::Kolkata, Tz};
+
+use crate::calendar::Calendar;
+use crate::payee::Payee;
+
+// Payment rails. Each rail accepts some kinds of payee, knows how to resolve
+// a payee to its registered name before money moves, and routes a payout to
+// one of its schemes with that scheme's limits, cut-off and settlement time.
+// Real rails call the PSP/NPCI or the sponsor bank; the mocks answer from a
+// small directory. Both mocks credit INR, NPR and LKR (standing in for UPI's
+// links with Nepal and Sri Lanka and their bank transfer schemes); limits are
+// on the payout's INR value whatever the currency.
+// - UPI pays VPAs and mobile numbers, instantly, up to UPI_MAX_INR (default
+//   ₹1,00,000).
+// - The bank rail pays account number + IFSC: by IMPS (instant,
+//   24x7) up to IMPS_MAX_INR (default ₹5,00,000), else by NEFT up to
+//   NEFT_MAX_INR (default ₹1,00,00,000). NEFT settles in the next half-hourly
+//   batch; after the NEFT cut-off in the calendar's rails.json (IST, 19:00 as
+//   shipped) it waits for the next day's first batch at 00:30.
+#[async_trait]
+pub trait PaymentRail: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Whether this rail can pay `payee`.
+    fn accepts(&self, payee: &Payee) -> bool;
+
+    /// Currencies it can credit receivers in.
+    fn currencies(&self) -> &'static [&'static str];
+
+    /// Resolve a payee to its registered name.
+    async fn lookup_payee(&self, payee: &Payee) -> anyhow::Result<PayeeLookup>;
+
+    /// Choose the scheme for a payout worth `amount_inr`, paid in
+    /// `dest_currency` at `now`, and when it settles.
+    fn route(&self, amount_inr: f64, dest_currency: &str, now: DateTime<Utc>) -> Result<Settlement, RailError>;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PayeeLookup {
+    Found { name: String },
+    NotFound,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settlement {
+    /// Scheme the payout goes over: "upi", "imps" or "neft"; stored as
+    /// `payments.rail`.
+    pub rail: &'static str,
+    /// When the receiver is credited.
+    pub settles_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum RailError {
+    #[error("{} payments can be at most ₹{max_inr:.2}; this one is ₹{amount_inr:.2}.", scheme_label(.rail))]
+    AboveLimit { rail: &'static str, max_inr: f64, amount_inr: f64 },
+    #[error("{} payouts can't be made in {currency}; choose INR as the receive currency.", payout_label(.rail))]
+    Currency { rail: &'static str, currency: String },
+}
+
+impl RailError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            RailError::AboveLimit { .. } => "rail_limit",
+            RailError::Currency { .. } => "rail_currency",
+        }
+    }
+}
+
+/// The rails in use; a payee goes to the first one that accepts it.
+pub struct Rails {
+    rails: Vec<Arc<dyn PaymentRail>>,
+}
+
+impl Rails {
+    pub fn from_env(calendar: &Calendar) -> anyhow::Result<Self> {
+        let directory = mock_directory()?;
+        Ok(Rails {
+            rails: vec![
+                Arc::new(MockUpiRail::from_env(directory.clone())),
+                Arc::new(MockBankRail::from_env(directory, calendar)?),
+            ],
+        })
+    }
+
+    /// Whether some rail credits receivers in `currency`; a payout currency
+    /// needs one before its corridors can open.
+    pub fn pays_out(&self, currency: &str) -> bool {
+        self.rails.iter().any(|r| r.currencies().contains(&currency))
+    }
+
+    pub fn for_payee(&self, payee: &Payee) -> Option<&dyn PaymentRail> {
+        self.rails.iter().find(|r| r.accepts(payee)).map(|r| r.as_ref())
+    }
+
+    /// The rail quotes are timed on when no payee is given (UPI).
+    pub fn primary(&self) -> &dyn PaymentRail {
+        self.rails[0].as_ref()
+    }
+}
+
+/// "IMPS" for "imps".
+pub fn scheme_label(rail: &str) -> String {
+    rail.to_uppercase()
+}
+
+/// "UPI" or "Bank account", for a rail's name.
+fn payout_label(rail: &str) -> String {
+    match rail {
+        "bank" => "Bank account".to_string(),
+        other => scheme_label(other),
+    }
+}
+
+/// When a payout settles, for the payer, in the receiver's timezone `tz`:
+/// "Credited instantly" or "Expected by 14:30 IST, 18 Oct".
+pub fn settlement_note(settles_at: DateTime<Utc>, paid_at: DateTime<Utc>, tz: Tz) -> String {
+    if settles_at <= paid_at + Duration::minutes(1) {
+        "Credited instantly".to_string()
+    } else {
+        format!("Expected by {}", settles_at.with_timezone(&tz).format("%H:%M %Z, %-d %b"))
+    }
+}
+
+/// Mock payee directory shared by the mock rails. Extra entries can be
+/// supplied as a JSON object `{ "vpa@handle": "Registered Name" }` via
+/// MOCK_PAYEE_DIRECTORY; accounts use their NPCI address
+/// (`<account>@<ifsc>.ifsc.npci`).
+fn mock_directory() -> anyhow::Result<HashMap<String, String>> {
+    let mut directory: HashMap<String, String> = [
+        ("9120744991@okrbi", "Edison Kumar"),
+        ("9120744991@upi", "Edison Kumar"),
+        ("priya.sharma@oksbi", "Priya Sharma"),
+        ("ravi@ybl", "Ravi Teja Reddy"),
+        ("kyc.helpdesk@paytm", "KYC Refund Helpdesk"),
+        ("50100123456789@hdfc0001234.ifsc.npci", "Lakshmi Narayanan"),
+        ("30219876543@sbin0004567.ifsc.npci", "Edison Kumar"),
+    ]
+    .iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect();
+    if let Ok(path) = std::env::var("MOCK_PAYEE_DIRECTORY") {
+        let raw = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("cannot read payee directory {}: {}", path, e))?;
+        let extra: HashMap<String, String> = serde_json::from_str(&raw)
+            .map_err(|e| anyhow::anyhow!("invalid payee directory {}: {}", path, e))?;
+        directory.extend(extra.into_iter().map(|(k, v)| (k.to_lowercase(), v)));
+    }
+    Ok(directory)
+}
+
+fn lookup(directory: &HashMap<String, String>, payee: &Payee) -> PayeeLookup {
+    match directory.get(&payee.address()) {
+        Some(name) => PayeeLookup::Found { name: name.clone() },
+        None => PayeeLookup::NotFound,
+    }
+}
+
+/// Currencies the mock rails credit.
+const MOCK_PAYOUTS: &[&str] = &["INR", "NPR", "LKR"];
+
+fn env_inr(var: &str, default: f64) -> f64 {
+    std::env::var(var).ok().and_then(|s| s.parse().ok()).filter(|v: &f64| *v > 0.0).unwrap_or(default)
+}
+
+/// Mock UPI rail over the mock directory.
+pub struct MockUpiRail {
+    directory: HashMap<String, String>,
+    max_inr: f64,
+}
+
+impl MockUpiRail {
+    fn from_env(directory: HashMap<String, String>) -> Self {
+        Self { directory, max_inr: env_inr("UPI_MAX_INR", 100_000.0) }
+    }
+}
+
+#[async_trait]
+impl PaymentRail for MockUpiRail {
+    fn name(&self) -> &'static str {
+        "upi"
+    }
+
+    fn accepts(&self, payee: &Payee) -> bool {
+        !matches!(payee, Payee::Account { .. })
+    }
+
+    fn currencies(&self) -> &'static [&'static str] {
+        MOCK_PAYOUTS
+    }
+
+    async fn lookup_payee(&self, payee: &Payee) -> anyhow::Result<PayeeLookup> {
+        Ok(lookup(&self.directory, payee))
+    }
+
+    fn route(&self, amount_inr: f64, dest_currency: &str, now: DateTime<Utc>) -> Result<Settlement, RailError> {
+        if !self.currencies().contains(&dest_currency) {
+            return Err(RailError::Currency { rail: "upi", currency: dest_currency.to_string() });
+        }
+        if amount_inr > self.max_inr {
+            return Err(RailError::AboveLimit { rail: "upi", max_inr: self.max_inr, amount_inr });
+        }
+        Ok(Settlement { rail: "upi", settles_at: now })
+    }
+}
+
+/// Mock IMPS/NEFT rail for bank accounts, over the mock directory.
+pub struct MockBankRail {
+    directory: HashMap<String, String>,
+    imps_max_inr: f64,
+    neft_max_inr: f64,
+    neft_cutoff: NaiveTime,
+}
+
+impl MockBankRail {
+    fn from_env(directory: HashMap<String, String>, calendar: &Calendar) -> anyhow::Result<Self> {
+        let neft_cutoff = calendar.rail("neft").map(|h| h.cutoff).ok_or_else(|| anyhow::anyhow!("calendar rails.json has no neft cut-off"))?;
+        Ok(Self { directory, imps_max_inr: env_inr("IMPS_MAX_INR", 500_000.0), neft_max_inr: env_inr("NEFT_MAX_INR", 10_000_000.0), neft_cutoff })
+    }
+
+    /// The NEFT batch a payout made at `now` goes in: the next half hour
+    /// (IST), or the next day's 00:30 batch after the cut-off.
+    fn neft_batch(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let local = now.with_timezone(&Kolkata);
+        let day = local.date_naive();
+        let batch = if local.time() >= self.neft_cutoff {
+            day.succ_opt().unwrap_or(day).and_hms_opt(0, 30, 0)
+        } else {
+            let next = (local.hour() * 60 + local.minute()) / 30 * 30 + 30;
+            match next {
+                1440 => day.succ_opt().unwrap_or(day).and_hms_opt(0, 0, 0),
+                m => day.and_hms_opt(m / 60, m % 60, 0),
+            }
+        };
+        batch
+            .and_then(|b| b.and_local_timezone(Kolkata).single())
+            .map(|b| b.with_timezone(&Utc))
+            .unwrap_or(now)
+    }
+}
+
+#[async_trait]
+impl PaymentRail for MockBankRail {
+    fn name(&self) -> &'static str {
+        "bank"
+    }
+
+    fn accepts(&self, payee: &Payee) -> bool {
+        matches!(payee, Payee::Account { .. })
+    }
+
+    fn currencies(&self) -> &'static [&'static str] {
+        MOCK_PAYOUTS
+    }
+
+    async fn lookup_payee(&self, payee: &Payee) -> anyhow::Result<PayeeLookup> {
+        Ok(lookup(&self.directory, payee))
+    }
+
+    fn route(&self, amount_inr: f64, dest_currency: &str, now: DateTime<Utc>) -> Result<Settlement, RailError> {
+        if !self.currencies().contains(&dest_currency) {
+            return Err(RailError::Currency { rail: "bank", currency: dest_currency.to_string() });
+        }
+        if amount_inr <= self.imps_max_inr {
+            return Ok(Settlement { rail: "imps", settles_at: now });
+        }
+        if amount_inr > self.neft_max_inr {
+            return Err(RailError::AboveLimit { rail: "neft", max_inr: self.neft_max_inr, amount_inr });
+        }
+        Ok(Settlement { rail: "neft", settles_at: self.neft_batch(now) })
+    }
+}
+
+/// Mask a registered name for display before payment: "Edison Kumar" -> "E****n K***r".
+pub fn mask_name(name: &str) -> String {
+    name.split_whitespace()
+        .map(|word| {
+            let chars: Vec<char> = word.chars().collect();
+            match chars.len() {
+                0 => String::new(),
+                1 | 2 => format!("{}*", chars[0]),
+                n => format!("{}{}{}", chars[0], "*".repeat(n - 2), chars[n - 1]),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    fn bank(cutoff: &str) -> MockBankRail {
+        MockBankRail {
+            directory: HashMap::new(),
+            imps_max_inr: 500_000.0,
+            neft_max_inr: 10_000_000.0,
+            neft_cutoff: NaiveTime::parse_from_str(cutoff, "%H:%M").unwrap(),
+        }
+    }
+
+    #[test]
+    fn neft_goes_in_the_next_half_hourly_batch() {
+        let rail = bank("19:00");
+        // 10:10 IST -> 10:30 IST
+        assert_eq!(rail.neft_batch(at("2026-10-19T04:40:00Z")), at("2026-10-19T05:00:00Z"));
+        // A payout on a batch boundary waits for the next one: 10:30 -> 11:00 IST
+        assert_eq!(rail.neft_batch(at("2026-10-19T05:00:00Z")), at("2026-10-19T05:30:00Z"));
+        // 18:59 IST -> 19:00 IST, the last batch before the cut-off
+        assert_eq!(rail.neft_batch(at("2026-10-19T13:29:59Z")), at("2026-10-19T13:30:00Z"));
+    }
+
+    #[test]
+    fn neft_after_the_cutoff_waits_for_the_next_day() {
+        let rail = bank("19:00");
+        // 19:00 IST and 23:45 IST -> 00:30 IST the next day
+        assert_eq!(rail.neft_batch(at("2026-10-19T13:30:00Z")), at("2026-10-19T19:00:00Z"));
+        assert_eq!(rail.neft_batch(at("2026-10-19T18:15:00Z")), at("2026-10-19T19:00:00Z"));
+        // Just after midnight IST is before the cut-off again: 00:10 -> 00:30 IST
+        assert_eq!(rail.neft_batch(at("2026-10-19T18:40:00Z")), at("2026-10-19T19:00:00Z"));
+    }
+
+    #[test]
+    fn neft_last_batch_of_the_day_rolls_to_midnight() {
+        // With a late cut-off, 23:40 IST goes in the 00:00 IST batch
+        let rail = bank("23:59");
+        assert_eq!(rail.neft_batch(at("2026-10-19T18:10:00Z")), at("2026-10-19T18:30:00Z"));
+    }
+
+    #[test]
+    fn bank_rail_routes_by_amount() {
+        let rail = bank("19:00");
+        let now = at("2026-10-19T04:40:00Z");
+        assert_eq!(rail.route(500_000.0, "INR", now), Ok(Settlement { rail: "imps", settles_at: now }));
+        assert_eq!(rail.route(500_000.01, "INR", now), Ok(Settlement { rail: "neft", settles_at: at("2026-10-19T05:00:00Z") }));
+        assert_eq!(rail.route(10_000_000.01, "INR", now).unwrap_err().code(), "rail_limit");
+        assert_eq!(rail.route(1_000.0, "NPR", now), Ok(Settlement { rail: "imps", settles_at: now }));
+        assert_eq!(rail.route(1_000.0, "BTN", now), Err(RailError::Currency { rail: "bank", currency: "BTN".into() }));
+    }
+
+    #[test]
+    fn upi_is_instant_up_to_its_limit() {
+        let rail = MockUpiRail { directory: HashMap::new(), max_inr: 100_000.0 };
+        let now = at("2026-10-19T20:00:00Z");
+        assert_eq!(rail.route(100_000.0, "INR", now), Ok(Settlement { rail: "upi", settles_at: now }));
+        assert_eq!(rail.route(100_000.01, "INR", now).unwrap_err().code(), "rail_limit");
+        assert_eq!(rail.route(10.0, "AED", now).unwrap_err().code(), "rail_currency");
+    }
+
+    #[test]
+    fn shipped_config_pays_aed_to_npr() {
+        use crate::currency::Registry;
+        use crate::fx::Rate;
+        use crate::limits::Limits;
+        use crate::pricing::Quote;
+        use crate::validation::{self, PaymentForm};
+
+        let currencies = Registry::from_env().unwrap();
+        let calendar = Calendar::from_env(&currencies).unwrap();
+        let rails = Rails::from_env(&calendar).unwrap();
+        let limits = Limits::from_env(&currencies).unwrap();
+        assert!(currencies.destinations().iter().all(|d| rails.pays_out(&d.code)));
+
+        let form = PaymentForm {
+            payer_name: "Priya Sharma".into(),
+            sender_name: None,
+            upi_or_mobile: "priya.sharma@oksbi".into(),
+            amount: Some(100.0),
+            dest_amount: None,
+            amount_inr: None,
+            currency: "AED".into(),
+            dest_currency: Some("NPR".into()),
+            note: None,
+            sid: None,
+            tz: None,
+            device_fp: None,
+        };
+        let valid = validation::payment_form(&form, &currencies, &crate::payee::Parser::default()).unwrap();
+        let now = at("2026-10-19T04:40:00Z");
+        let leg = |base: &str, to_inr: f64| Rate { base: base.into(), quote: "INR".into(), rate: to_inr, quote_inr: 1.0, fetched_at: Some(now), provider: "live".into() };
+        let rate = Rate::cross(&leg("AED", 22.6), &leg("NPR", 0.625));
+        let quote = Quote::forward(valid.value, &rate, currencies.precision("AED", "NPR"));
+        assert_eq!((quote.dest_currency.as_str(), quote.dest_amount), ("NPR", 3_616.0));
+        limits.check_quote(&quote, &currencies).unwrap();
+
+        let rail = rails.for_payee(&valid.payee).unwrap();
+        assert_eq!(rail.route(quote.amount_inr, &quote.dest_currency, now), Ok(Settlement { rail: "upi", settles_at: now }));
+        assert_eq!(calendar.timezone_for("NPR"), chrono_tz::Asia::Kathmandu);
+    }
+}
@@ -1,4 +1,4 @@
-use axum::{routing::{get, post}, Router, extract::{State, Query}, response::{Html, IntoResponse, Redirect, Response}, Form, Json};
+use axum::{routing::{get, post}, Router, extract::{State, Query, Path}, response::{Html, IntoResponse, Redirect, Response}, Form, Json};
 use axum::http::StatusCode;
 use tower_http::services::ServeDir;
 use serde::Deserialize;
@@ -9,6 +9,23 @@ use std::collections::{HashSet};
 
 use crate::{AppState};
 use crate::ai;
+use crate::db;
+use crate::admin;
+use crate::client::{self, ClientInfo};
+use crate::rails::{self, PayeeLookup};
+use crate::kb;
+use crate::explain;
+use crate::assistant;
+use crate::lang;
+use crate::followup;
+use crate::pricing;
+use crate::optimizer;
+use crate::alerts;
+use crate::limits;
+use crate::calendar;
+use crate::validation::{self, PaymentForm};
+use crate::payee::Payee;
+use crate::error::{self as app_error, AppError, ErrorKind};
 
 pub fn router(state: AppState) -> Router {
     Router::new()
@@ -21,7 +38,18 @@ pub fn router(state: AppState) -> Router {
         .route("/success", get(success))
         .route("/ask", post(ask_ai))
         .route("/optimize_currency", get(optimize_currency))
+        .route("/api/v1/payments/:id", get(api_get_payment))
+        .route("/api/v1/payee/lookup", get(api_payee_lookup))
+        .route("/api/v1/quote", get(api_quote))
+        .route("/api/v1/corridors", get(api_corridors))
+        .route("/api/v1/currencies", get(api_currencies))
+        .route("/api/v1/fx/history", get(api_fx_history))
+        .route("/api/v1/alerts", post(api_create_alert))
+        .route("/api/v1/alerts/:id", get(api_get_alert).delete(api_cancel_alert))
+        .route("/api/v1/alerts/:id/confirm", get(api_confirm_alert))
+        .nest("/admin", admin::router())
         .nest_service("/static", ServeDir::new("static"))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), app_error::render))
         .with_state(state)
 }
 
@@ -56,37 +84,6 @@ async fn index(State(state): State<AppState>) -> Html<String> {
     Html(body)
 }
 
-#[derive(Debug, Deserialize)]
-struct PaymentForm {
-    payer_name: String,
-    upi_or_mobile: String,
-    amount: f64,
-    currency: String,
-    note: Option<String>,
-    sid: Option<String>,
-}
-
-fn normalize_upi(input: &str) -> String {
-    let s = input.trim();
-    if s.chars().all(|c| c.is_ascii_digit()) && (10..=12).contains(&s.len()) {
-        format!("{}@upi", s)
-    } else {
-        s.to_string()
-    }
-}
-
-fn upi_deeplink(pa: &str, pn: &str, amount: f64, note: Option<&str>) -> String {
-    // Format with 2 decimal places for INR
-    let am = format!("{:.2}", amount);
-    let mut url = format!("upi://pay?pa={}&pn={}&am={}&cu=INR", urlencoding::encode(pa), urlencoding::encode(pn), am);
-    if let Some(n) = note {
-        if !n.trim().is_empty() {
-            url.push_str(&format!("&tn={}", urlencoding::encode(n)));
-        }
-    }
-    url
-}
-
 fn qr_data_url(data: &str) -> String {
     use qrcode::QrCode;
 use image::{Luma, ImageBuffer};
@@ -101,87 +98,247 @@ use base64::Engine as _;
     format!("data:image/png;base64,{}", b64)
 }
 
-async fn pay_form(State(state): State<AppState>, Query(params): Query<std::collections::HashMap<String, String>>) -> Html<String> {
+async fn pay_form(State(state): State<AppState>, client: ClientInfo, Query(params): Query<std::collections::HashMap<String, String>>) -> Html<String> {
     let mut ctx = Context::new();
-    if let Some(sid) = params.get("sid") { ctx.insert("sid", sid); }
+    insert_currencies(&state, &mut ctx);
+    if let Some(sid) = params.get("sid") {
+        ctx.insert("sid", sid);
+        if let Ok(id) = Uuid::parse_str(sid) {
+            let _ = state.db.set_session_client(id, client.ip_string().as_deref(), client.user_agent.as_deref(), None).await;
+            if let Ok(Some(s)) = state.db.get_session(id).await {
+                if session_expired(&s, state.clock.now()) { ctx.insert("session_expired", &true); }
+            }
+        }
+    }
     let body = state.templates.render("pay_form.html", &ctx).unwrap_or_else(|e| format!("Template error: {}", e));
     Html(body)
 }
 
+/// Result of asking the rail who a payee is, compared with the typed name.
+struct PayeeVerification {
+    status: &'static str,
+    registered_name: Option<String>,
+    similarity: Option<f64>,
+}
+
+impl PayeeVerification {
+    fn check(&self) -> ai::PayeeCheck<'_> {
+        match (&self.registered_name, self.similarity) {
+            (Some(name), Some(similarity)) => ai::PayeeCheck::Verified { registered_name: name, similarity },
+            _ => ai::PayeeCheck::Unverified,
+        }
+    }
+}
+
+async fn verify_payee(rail: &dyn rails::PaymentRail, payee: &Payee, typed_name: &str) -> PayeeVerification {
+    match rail.lookup_payee(payee).await {
+        Ok(PayeeLookup::Found { name }) => PayeeVerification {
+            status: "verified",
+            similarity: Some(ai::name_similarity(typed_name, &name)),
+            registered_name: Some(name),
+        },
+        Ok(PayeeLookup::NotFound) => PayeeVerification { status: "not_found", registered_name: None, similarity: None },
+        Err(e) => {
+            tracing::warn!(error = %e, rail = rail.name(), "payee lookup failed");
+            PayeeVerification { status: "error", registered_name: None, similarity: None }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct PayeeLookupQuery { payee: String }
+
+// Pre-submit beneficiary check for pay_form.html; only a masked name is
+// returned, and comparing it with the typed name is left to the payer so the
+// endpoint can't confirm guesses. Lookups are throttled per client IP.
+async fn api_payee_lookup(State(state): State<AppState>, client: ClientInfo, Query(q): Query<PayeeLookupQuery>) -> Response {
+    let key = client.ip_string().unwrap_or_default();
+    if !state.payee_lookups.allow(&key, state.clock.now()) {
+        let body = serde_json::json!({ "status": "rate_limited", "error": "Too many receiver checks. Try again in a few minutes." });
+        return (StatusCode::TOO_MANY_REQUESTS, Json(body)).into_response();
+    }
+    let payee = match state.payees.parse(&q.payee) {
+        Ok(p) => p,
+        Err(e) => {
+            return Json(serde_json::json!({ "payee": q.payee.trim(), "status": "invalid", "code": e.code(), "error": e.to_string() })).into_response();
+        }
+    };
+    let Some(rail) = state.rails.for_payee(&payee) else {
+        return Json(serde_json::json!({ "payee": payee.address(), "payee_type": payee.kind(), "status": "error" })).into_response();
+    };
+    let v = verify_payee(rail, &payee, "").await;
+    Json(serde_json::json!({
+        "payee": payee.address(),
+        "payee_type": payee.kind(),
+        "display": payee.display(),
+        "rail": rail.name(),
+        "status": v.status,
+        "masked_name": v.registered_name.as_deref().map(rails::mask_name),
+    }))
+    .into_response()
+}
+
 #[derive(Deserialize)]
 struct WithSid {
     sid: Option<String>,
 }
 
-async fn create_payment(State(state): State<AppState>, Query(q): Query<WithSid>, Form(form): Form<PaymentForm>) -> Html<String> {
-    let upi_id = normalize_upi(&form.upi_or_mobile);
-    let src_ccy = form.currency.trim().to_uppercase();
-    let (rate, rate_ts, provider) = if src_ccy == "INR" {
-        (1.0, Some(Utc::now()), Some("static".to_string()))
-    } else {
-        match fetch_rate_to_inr(&src_ccy).await {
-            Ok((r, ts, prov)) => (r, Some(ts), Some(prov)),
-            Err(_) => {
-                let r = fallback_rate(&src_ccy);
-                (r, None, Some("fallback".to_string()))
-            }
-        }
+/// Price a payment, rounded to its currencies' minor units, and check the
+/// source amount against the currency and corridor limits.
+fn price(state: &AppState, mode: pricing::QuoteMode, value: f64, rate: &crate::fx::Rate) -> Result<pricing::Quote, limits::LimitError> {
+    let precision = state.currencies.precision(&rate.base, &rate.quote);
+    let quote = match mode {
+        pricing::QuoteMode::Forward => pricing::Quote::forward(value, rate, precision),
+        pricing::QuoteMode::Reverse => pricing::Quote::reverse(value, rate, precision),
     };
+    state.limits.check_quote(&quote, &state.currencies)?;
+    Ok(quote)
+}
 
-    // Convert to INR amount with 2 decimals (receiver credit)
-    let amount_inr = (form.amount * rate * 100.0).round() / 100.0;
-    // AI risk assessment (demo heuristics)
-    let risk = ai::assess_risk(&upi_id, &src_ccy, amount_inr, form.note.as_deref());
-    // Fee components (transfer + platform). For INR source, no charges.
-    let (fee_transfer_inr_comp, fee_platform_inr_comp) = if src_ccy == "INR" {
-        (0.0_f64, 0.0_f64)
+/// Currency options for the payer form, from the registry.
+pub fn insert_currencies(state: &AppState, ctx: &mut Context) {
+    ctx.insert("currencies", &state.currencies.sources());
+    ctx.insert("destinations", &state.currencies.destinations());
+}
+
+/// Why `source` can't pay out in `dest`, if it can't.
+fn corridor_error(state: &AppState, source: &str, dest: &str) -> Option<String> {
+    if state.currencies.allows(source, dest) {
+        None
     } else {
-        (99.0_f64, 25.0_f64)
+        Some(format!("Payments from {} to {} are not available", source, dest))
+    }
+}
+
+async fn create_payment(State(state): State<AppState>, client: ClientInfo, Query(q): Query<WithSid>, Form(form): Form<PaymentForm>) -> Response {
+    let sid_for_errors = q.sid.clone().or(form.sid.clone());
+    match place_payment(&state, client, q, &form).await {
+        Ok(page) => page,
+        Err(e) => e.on_form(form.echo(), sid_for_errors.as_ref()).into_response(),
+    }
+}
+
+async fn place_payment(state: &AppState, client: ClientInfo, q: WithSid, form: &PaymentForm) -> Result<Response, AppError> {
+    let valid = validation::payment_form(form, &state.currencies, &state.payees).map_err(AppError::invalid)?;
+    let src_ccy = &valid.source_currency;
+    let upi_id = &valid.upi_id;
+    let now = state.clock.now();
+    let client_ip = client.ip_string();
+    let device_fp = client::sanitize_fingerprint(form.device_fp.as_deref());
+    let ip_country = match (&state.geoip, client.ip) {
+        (Some(g), Some(ip)) => g.country(ip),
+        _ => None,
     };
-    let fee_inr: f64 = fee_transfer_inr_comp + fee_platform_inr_comp;
-    // Fees in source currency (if not INR)
-    let fee_src: f64 = if src_ccy == "INR" { 0.0 } else { (fee_inr / rate * 100.0).round() / 100.0 };
-    // Totals debited
-    let total_inr: f64 = ((amount_inr + fee_inr) * 100.0).round() / 100.0;
-    let total_src: f64 = ((form.amount + fee_src) * 100.0).round() / 100.0;
-
-    // Store fx rate record (best-effort)
-    if src_ccy != "INR" {
-        if let Some(ts) = rate_ts {
-            let _ = state.db.insert_fx_rate(&src_ccy, "INR", rate, provider.as_deref(), Some(ts)).await;
-        } else {
-            let _ = state.db.insert_fx_rate(&src_ccy, "INR", rate, provider.as_deref(), None).await;
+    let payer_tz: Option<chrono_tz::Tz> = form.tz.as_deref().and_then(|t| t.trim().parse().ok());
+    // Fail closed: no payment is priced without a fresh, sane live rate
+    let rate = state.rates.rate(src_ccy, &valid.dest_currency, now).await?;
+    // Receiver credit, fees and totals: same pricing as the optimizer's quotes
+    let quote = price(state, valid.mode, valid.value, &rate)?;
+    // Cumulative payer limits count this payment with the payer's recent ones,
+    // by device and by IP
+    if let Some(caps) = &state.limits.payer {
+        let mut totals = Vec::new();
+        for payer in limits::Payer::all(device_fp.as_deref(), client_ip.as_deref()) {
+            totals.push(state.db.payer_credits_inr(payer, limits::window_starts(now)).await?);
+        }
+        let used = limits::heaviest(&totals);
+        // Under the caps at the floor rate is under them at the live rate too
+        let at_floor = caps.min_rate_inr.map(|floor| caps.check(quote.amount_inr, used, floor));
+        if !matches!(at_floor, Some(Ok(()))) {
+            match (state.rates.rate_to_inr(&caps.currency, now).await, at_floor) {
+                (Ok(limit_rate), _) => caps.check(quote.amount_inr, used, limit_rate.rate)?,
+                // Without a live rate the caps hold at the floor
+                (Err(_), Some(at_floor)) => at_floor?,
+                (Err(e), None) => return Err(e.into()),
+            }
         }
     }
+    let amount_inr = quote.amount_inr;
+    // UPI for VPAs and mobiles, IMPS/NEFT for bank accounts
+    let rail = state.rails.for_payee(&valid.payee).ok_or_else(|| anyhow::anyhow!("no rail pays {} payees", valid.payee.kind()))?;
+    let settlement = rail.route(amount_inr, &quote.dest_currency, now)?;
+    let credit_date = state.calendar.credit_date(settlement.rail, src_ccy, &quote.dest_currency, now, settlement.settles_at);
+    // AI risk assessment (demo heuristics)
+    // Without the payer's name there is nothing to count per device
+    let device_payer_names = match (&device_fp, &valid.sender_name) {
+        (Some(fp), Some(sender_name)) => state.db.device_payer_names(fp, sender_name, now - chrono::Duration::hours(24)).await.unwrap_or(1),
+        _ => 0,
+    };
+    let payee = verify_payee(rail, &valid.payee, &valid.payer_name).await;
+    let risk_input = ai::RiskInput {
+        upi_id,
+        src_currency: src_ccy,
+        amount_inr,
+        note: valid.note.as_deref(),
+        at: now,
+        payer_tz,
+        device_payer_names,
+        ip_country: ip_country.as_deref(),
+        payee: payee.check(),
+    };
+    let risk = ai::assess_risk(&risk_input, &state.payees);
 
+    let risk_summary = risk.summary("en");
     let id = state
         .db
-        .insert_payment(
-            &form.payer_name,
-            &upi_id,
+        .insert_payment(&db::NewPayment {
+            payer_name: &valid.payer_name,
+            sender_name: valid.sender_name.as_deref(),
+            upi_id,
             amount_inr,
-            form.note.as_deref(),
-            &src_ccy,
-            form.amount,
-            Some(rate),
-            rate_ts,
-            fee_transfer_inr_comp,
-            fee_platform_inr_comp,
-            fee_src,
-            total_inr,
-            total_src,
-            risk.score,
-            &risk.label,
-            Some(&risk.reasons.join(", ")),
-        )
-        .await
-        .expect("DB insert failed");
+            note: valid.note.as_deref(),
+            source_currency: src_ccy,
+            source_amount: quote.source_amount,
+            rate_to_inr: Some(quote.rate * rate.quote_inr),
+            rate_timestamp: quote.rate_timestamp,
+            rate_provider: Some(&quote.rate_provider),
+            fee_transfer_inr: quote.fee_transfer_inr,
+            fee_platform_inr: quote.fee_platform_inr,
+            fee_src_total: quote.fee_src,
+            total_inr: quote.total_inr,
+            total_src: quote.total_src,
+            risk_score: risk.score,
+            risk_label: &risk.label,
+            risk_reasons: Some(&risk_summary),
+            risk_reason_codes: &risk.reasons,
+            payer_tz: payer_tz.map(|t| t.name()),
+            client_ip: client_ip.as_deref(),
+            user_agent: client.user_agent.as_deref(),
+            device_fp: device_fp.as_deref(),
+            ip_country: ip_country.as_deref(),
+            payee_lookup: Some(payee.status),
+            payee_registered_name: payee.registered_name.as_deref(),
+            payee_name_similarity: payee.similarity,
+            dest_currency: &quote.dest_currency,
+            dest_amount: quote.dest_amount,
+            rate_to_dest: Some(quote.rate),
+            rail: settlement.rail,
+            settles_at: Some(settlement.settles_at),
+            expected_credit_date: Some(credit_date),
+        })
+        .await?;
+
+    // Shadow-mode candidate scoring: stored for comparison only, never affects the decision
+    if let Some(rules) = &state.shadow_rules {
+        let shadow = rules.assess(&risk_input);
+        if let Err(e) = state.db.insert_risk_shadow(id, &rules.version, shadow.score, &shadow.label, &shadow.reasons).await {
+            tracing::warn!(error = %e, payment_id = %id, "shadow risk insert failed");
+        }
+    }
 
     let sid_opt = q.sid.clone().or(form.sid.clone()).or_else(|| std::env::var("SID").ok());
     if let Some(sid_str) = sid_opt.clone() {
         if let Ok(sid) = Uuid::parse_str(&sid_str) {
-            let _ = state.db.set_session_status(sid, "processing").await;
-            let _ = state.db.attach_payment_to_session(sid, id).await;
+            // An expired desktop session no longer shows its QR; don't revive it
+            let live = match state.db.get_session(sid).await.ok().flatten() {
+                Some(s) => !session_expired(&s, now),
+                None => false,
+            };
+            let _ = state.db.set_session_client(sid, client_ip.as_deref(), client.user_agent.as_deref(), device_fp.as_deref()).await;
+            if live {
+                let _ = state.db.set_session_status(sid, "processing").await;
+                let _ = state.db.attach_payment_to_session(sid, id).await;
+            }
         }
     }
 
@@ -189,65 +346,29 @@ async fn create_payment(State(state): State<AppState>, Query(q): Query<WithSid>,
     let mut ctx = Context::new();
     ctx.insert("id", &id.to_string());
     ctx.insert("amount_inr", &amount_inr);
-    ctx.insert("source_amount", &form.amount);
-    ctx.insert("source_currency", &src_ccy);
-    ctx.insert("fee_inr", &format!("{:.2}", fee_inr));
-    ctx.insert("fee_src", &format!("{:.2}", fee_src));
-    ctx.insert("total_inr", &format!("{:.2}", total_inr));
-    ctx.insert("total_src", &format!("{:.2}", total_src));
-    ctx.insert("rate", &rate);
+    ctx.insert("payee", &valid.payee.display());
+    ctx.insert("rail", &rails::scheme_label(settlement.rail));
+    ctx.insert("settlement", &calendar::credit_note(credit_date, settlement.settles_at, now, state.calendar.timezone_for(&quote.dest_currency)));
+    ctx.insert("source_amount", &quote.source_amount);
+    ctx.insert("dest_currency", &quote.dest_currency);
+    ctx.insert("dest_amount", &format!("{:.2}", quote.dest_amount));
+    ctx.insert("source_currency", src_ccy);
+    ctx.insert("fee_inr", &format!("{:.2}", quote.fee_inr));
+    ctx.insert("fee_src", &format!("{:.2}", quote.fee_src));
+    ctx.insert("total_inr", &format!("{:.2}", quote.total_inr));
+    ctx.insert("total_src", &format!("{:.2}", quote.total_src));
+    ctx.insert("rate", &quote.rate);
     ctx.insert("risk_label", &risk.label);
     ctx.insert("risk_score", &risk.score);
-    if !risk.reasons.is_empty() { ctx.insert("risk_reasons", &risk.reasons.join(", ")); }
+    if !risk.reasons.is_empty() { ctx.insert("risk_reasons", &risk_summary); }
     if let Some(sid) = sid_opt { ctx.insert("sid", &sid); }
     let body = state.templates.render("processing.html", &ctx).unwrap_or_else(|e| format!("Template error: {}", e));
-    Html(body)
+    Ok(Html(body).into_response())
 }
 
-async fn fetch_rate_to_inr(base: &str) -> anyhow::Result<(f64, DateTime<Utc>, String)> {
-    if base == "INR" { return Ok((1.0, Utc::now(), "exchangerate.host-live".into())); }
-    // Use exchangerate.host live endpoint with USD as the source; compute base->INR = USDINR / USDBASE
-    let key = std::env::var("FX_API_KEY").unwrap_or_else(|_| "404823e62fd25735ff3f46242b2340f9".to_string());
-    let base_up = base.to_uppercase();
-    // Limit currencies to the two we need
-    let currencies = if base_up == "USD" { "INR".to_string() } else { format!("INR,{}", base_up) };
-    let url = format!("https://api.exchangerate.host/live?access_key={}&currencies={}", key, currencies);
-    let resp = reqwest::Client::new().get(&url).send().await?;
-    if !resp.status().is_success() {
-        anyhow::bail!("rate http status {}", resp.status());
-    }
-    let v: serde_json::Value = resp.json().await?;
-    if !v["success"].as_bool().unwrap_or(false) {
-        anyhow::bail!("rate api returned error: {}", v);
-    }
-    let quotes = v["quotes"].as_object().ok_or_else(|| anyhow::anyhow!("missing quotes"))?;
-    let usd_inr = quotes.get("USDINR").and_then(|x| x.as_f64()).ok_or_else(|| anyhow::anyhow!("missing USDINR"))?;
-    let rate = if base_up == "USD" {
-        usd_inr
-    } else {
-        let key = format!("USD{}", base_up);
-        let usd_base = quotes.get(&key).and_then(|x| x.as_f64()).ok_or_else(|| anyhow::anyhow!("missing {}", key))?;
-        if usd_base == 0.0 { anyhow::bail!("invalid 0 rate for {}", key); }
-        usd_inr / usd_base
-    };
-    let ts = v["timestamp"].as_i64().unwrap_or_else(|| Utc::now().timestamp());
-    let dt = chrono::DateTime::<Utc>::from_timestamp(ts, 0).unwrap_or_else(|| Utc::now());
-    Ok((rate, dt, "exchangerate.host-live".into()))
-}
-
-fn fallback_rate(base: &str) -> f64 {
-    // Very rough demo fallback; update as needed.
-    match base {
-        "AED" => 22.5,
-        "NPR" => 0.63,
-        "BTN" => 1.0,
-        "SGD" => 61.0,
-        "MUR" => 1.7,
-        "EUR" => 90.0,
-        "LKR" => 0.25,
-        _ => 1.0,
-    }
-}
+/// The success page shows payment details and asks about them with the
+/// session ID, so it only runs its own scripts and can't be framed.
+const SUCCESS_CSP: &str = "default-src 'self'; img-src 'self' data:; style-src 'self' 'unsafe-inline'; script-src 'self'; base-uri 'none'; form-action 'self'; frame-ancestors 'none'";
 
 async fn success(State(state): State<AppState>, axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>, headers: axum::http::HeaderMap) -> Response {
     let id_str = params.get("id").cloned().unwrap_or_default();
@@ -286,6 +407,8 @@ async fn success(State(state): State<AppState>, axum::extract::Query(params): ax
     if let Some(a) = source_amount { ctx.insert("source_amount", &format!("{:.2}", a)); }
     if let Some(c) = &source_currency { ctx.insert("source_currency", c); }
     if let Some(r) = rate_opt { ctx.insert("rate", &r); }
+    // The payment Q&A widget asks with the session; /ask checks ownership
+    if let Some(sid) = params.get("sid").and_then(|s| Uuid::parse_str(s).ok()) { ctx.insert("sid", &sid.to_string()); }
     // Query back the payment to pull persisted totals
     if let Some(pid) = id {
         if let Ok(Some(p)) = state.db.get_payment(pid).await {
@@ -294,13 +417,25 @@ async fn success(State(state): State<AppState>, axum::extract::Query(params): ax
             ctx.insert("fee_src", &format!("{:.2}", p.fee_src_total));
             ctx.insert("total_inr", &format!("{:.2}", p.total_inr));
             ctx.insert("total_src", &format!("{:.2}", p.total_src));
+            ctx.insert("dest_currency", &p.dest_currency);
+            ctx.insert("dest_amount", &format!("{:.2}", p.dest_amount));
+            ctx.insert("rail", &rails::scheme_label(&p.rail));
+            match (p.expected_credit_date, p.settles_at) {
+                (Some(day), Some(at)) => ctx.insert("settlement", &calendar::credit_note(day, at, p.created_at, state.calendar.timezone_for(&p.dest_currency))),
+                (None, Some(at)) => ctx.insert("settlement", &rails::settlement_note(at, p.created_at, state.calendar.timezone_for(&p.dest_currency))),
+                _ => {}
+            }
             if let Some(lbl) = p.risk_label.clone() { ctx.insert("risk_label", &lbl); }
             if let Some(sc) = p.risk_score { ctx.insert("risk_score", &sc); }
-            if let Some(rn) = p.risk_reasons.clone() { ctx.insert("risk_reasons", &rn); }
+            if !p.risk_reason_codes.is_empty() {
+                ctx.insert("risk_reasons", &ai::reasons_summary(&p.risk_reason_codes, "en"));
+            } else if let Some(rn) = p.risk_reasons.clone() {
+                ctx.insert("risk_reasons", &rn);
+            }
         }
     }
     let body = state.templates.render("success.html", &ctx).unwrap_or_else(|e| format!("Template error: {}", e));
-    Html(body).into_response()
+    ([(axum::http::header::CONTENT_SECURITY_POLICY, SUCCESS_CSP)], Html(body)).into_response()
 }
 
 async fn processing(State(state): State<AppState>, Query(params): Query<std::collections::HashMap<String, String>>) -> Html<String> {
@@ -311,10 +446,22 @@ async fn processing(State(state): State<AppState>, Query(params): Query<std::col
     Html(body)
 }
 
+// Pending sessions expire after SESSION_TTL_SECS (default 15 minutes); once a
+// payment is in flight the session stays valid so the desktop can finish.
+fn session_ttl() -> chrono::Duration {
+    let secs: i64 = std::env::var("SESSION_TTL_SECS").ok().and_then(|s| s.parse().ok()).unwrap_or(900);
+    chrono::Duration::seconds(secs)
+}
+
+fn session_expired(s: &db::Session, now: DateTime<Utc>) -> bool {
+    s.status == "pending" && now - s.created_at > session_ttl()
+}
+
 async fn session_status(State(state): State<AppState>, Query(params): Query<std::collections::HashMap<String, String>>) -> impl IntoResponse {
     let sid_str = params.get("sid").cloned().unwrap_or_default();
     let status = if let Ok(sid) = Uuid::parse_str(&sid_str) {
         match state.db.get_session(sid).await.ok().flatten() {
+            Some(s) if session_expired(&s, state.clock.now()) => "expired".into(),
             Some(s) => s.status,
             None => "not_found".into(),
         }
@@ -332,33 +479,359 @@ async fn session_processing(State(state): State<AppState>, Query(params): Query<
 }
 
 #[derive(Deserialize)]
-struct AskReq { question: String }
+struct AskReq {
+    question: String,
+    /// Ask about a specific payment; must belong to `session_id`.
+    payment_id: Option<Uuid>,
+    /// The payer's session: keeps conversation history, and its attached
+    /// payment (if any) is the context.
+    session_id: Option<Uuid>,
+    lang: Option<String>,
+}
+
+/// The session a question belongs to and the payment it may be about.
+/// Asking about a payment requires the session that created it: a bare
+/// payment_id, a foreign payment or an unknown session all get the same
+/// refusal so IDs can't be probed.
+async fn ask_scope(state: &AppState, req: &AskReq) -> Result<(Option<db::Session>, Option<db::Payment>), Response> {
+    let denied = || {
+        (StatusCode::FORBIDDEN, Json(serde_json::json!({ "error": "payment not available for this session" }))).into_response()
+    };
+    let Some(sid) = req.session_id else {
+        return if req.payment_id.is_some() { Err(denied()) } else { Ok((None, None)) };
+    };
+    let session = state.db.get_session(sid).await.ok().flatten().ok_or_else(denied)?;
+    let pid = match (req.payment_id, session.payment_id) {
+        (Some(requested), Some(own)) if requested == own => Some(own),
+        (Some(_), _) => return Err(denied()),
+        (None, own) => own,
+    };
+    let payment = match pid {
+        Some(pid) => Some(state.db.get_payment(pid).await.ok().flatten().ok_or_else(denied)?),
+        None => None,
+    };
+    Ok((Some(session), payment))
+}
+
+async fn ask_ai(State(state): State<AppState>, Json(req): Json<AskReq>) -> Response {
+    let (session, payment) = match ask_scope(&state, &req).await {
+        Ok(scope) => scope,
+        Err(resp) => return resp,
+    };
+    let session_id = session.as_ref().map(|s| s.id);
+    // Follow-ups like "and for EUR?" are answered as the previous question
+    // with the new currency or amount swapped in
+    let previous = match session_id {
+        Some(sid) => state.db.last_assistant_turn(sid).await.unwrap_or_else(|e| {
+            tracing::error!(error = %e, "assistant history lookup failed");
+            None
+        }),
+        None => None,
+    };
+    let question = followup::resolve(&req.question, previous.as_ref().map(|t| t.resolved_question.as_str()))
+        .unwrap_or_else(|| req.question.clone());
+    // The question's script decides the answer language; the client's
+    // `lang` only breaks a Hindi/Nepali tie
+    let answer_lang = lang::detect(&question, req.lang.as_deref());
+
+    // Payment answers are English-only: a question in another language that
+    // names a topic still gets one, marked "en", rather than a generic answer
+    let explained = payment.as_ref().and_then(|p| explain::explain(&question, p));
+    let (mut body, (answer, turn_lang, backend, fallback, confidence)) = match explained {
+        Some(answer) => {
+            if answer_lang != "en" {
+                tracing::info!(asked = answer_lang, "payment explanation answered in English");
+            }
+            let body = serde_json::json!({
+                "answer": answer,
+                "passages": [],
+                "confidence": 1.0,
+                "fallback": false,
+                "payment_id": payment.as_ref().map(|p| p.id),
+                "lang": "en",
+            });
+            (body, (answer, "en", "explain", false, Some(1.0)))
+        }
+        None => {
+            let a = assistant::answer(state.assistant.as_ref(), &question, answer_lang).await;
+            let body = serde_json::json!({
+                "answer": a.text,
+                "passages": a.passages.iter().map(|h| serde_json::json!({
+                    "article": h.passage.article,
+                    "title": h.passage.title,
+                    "heading": h.passage.heading,
+                    "text": h.passage.text,
+                    "confidence": kb::confidence(h.score),
+                })).collect::<Vec<_>>(),
+                "confidence": a.confidence,
+                "fallback": a.fallback,
+                "backend": a.backend,
+                "lang": a.lang,
+            });
+            (body, (a.text, a.lang, a.backend, a.fallback, a.confidence))
+        }
+    };
+    let record = db::NewAssistantTurn {
+        session_id,
+        payment_id: payment.as_ref().map(|p| p.id),
+        question: &req.question,
+        resolved_question: &question,
+        answer: &answer,
+        lang: turn_lang,
+        backend,
+        fallback,
+        confidence,
+    };
+    if let Err(e) = state.db.insert_assistant_turn(&record).await {
+        tracing::error!(error = %e, "assistant turn insert failed");
+    }
+    if question != req.question {
+        body["resolved_question"] = serde_json::Value::String(question);
+    }
+    Json(body).into_response()
+}
 
-async fn ask_ai(Json(req): Json<AskReq>) -> impl IntoResponse {
-    let ans = ai::answer_faq(&req.question);
-    Json(serde_json::json!({ "answer": ans }))
+#[derive(Deserialize)]
+struct OptQuery {
+    amount: Option<f64>,
+    allowed: Option<String>,
+    /// Target receiver credit; switches to planning from held balances.
+    amount_inr: Option<f64>,
+    /// Balances the payer holds, e.g. "500 AED, 100 EUR". Without them every
+    /// allowed currency is treated as unlimited.
+    balances: Option<String>,
+    /// Currency plan costs are compared in (default INR).
+    reference: Option<String>,
 }
 
 #[derive(Deserialize)]
-struct OptQuery { amount: Option<f64>, allowed: Option<String> }
+struct QuoteQuery {
+    currency: String,
+    amount: Option<f64>,
+    /// Payout currency (default INR).
+    dest: Option<String>,
+    dest_amount: Option<f64>,
+    amount_inr: Option<f64>,
+    /// Payee, to time the payout on its rail (UPI when absent or invalid).
+    payee: Option<String>,
+}
 
-async fn optimize_currency(Query(q): Query<OptQuery>) -> impl IntoResponse {
-    let amount = q.amount.unwrap_or(0.0).max(0.0);
-    // Server-side whitelist (authoritative): env ALLOWED_CURRENCIES="INR,AED,..." or fallback
-    let default_ccys = ["INR","AED","NPR","BTN","SGD","MUR","EUR","LKR"];
-    let server_allowed: HashSet<String> = std::env::var("ALLOWED_CURRENCIES")
-        .ok()
-        .and_then(|s| {
-            let v: Vec<String> = s
-                .split(',')
-                .map(|x| x.trim().to_uppercase())
-                .filter(|x| !x.is_empty())
-                .collect();
-            if v.is_empty() { None } else { Some(v) }
-        })
-        .unwrap_or_else(|| default_ccys.iter().map(|s| s.to_string()).collect())
+/// Price a payment without making it: forward with `amount`, or reverse with
+/// `dest_amount` (what the receiver should get; `amount_inr` for INR payouts).
+/// Includes the rail and the expected credit date.
+async fn api_quote(State(state): State<AppState>, Query(q): Query<QuoteQuery>) -> Result<Response, AppError> {
+    let currency = q.currency.trim().to_uppercase();
+    let dest = q.dest.as_deref().map(|c| c.trim().to_uppercase()).unwrap_or_else(|| "INR".into());
+    if let Some(msg) = corridor_error(&state, &currency, &dest) {
+        return Err(AppError::bad_request(msg));
+    }
+    let (mode, value) = validation::receive_amount(&dest, q.dest_amount, q.amount_inr)
+        .and_then(|receive| validation::amount_spec(q.amount, receive))
+        .map_err(AppError::bad_request)?;
+    let now = state.clock.now();
+    let rate = state.rates.rate(&currency, &dest, now).await?;
+    let quote = price(&state, mode, value, &rate)?;
+    let payee = q.payee.as_deref().filter(|p| !p.trim().is_empty()).and_then(|p| state.payees.parse(p).ok());
+    let rail = payee.as_ref().and_then(|p| state.rails.for_payee(p)).unwrap_or(state.rails.primary());
+    let settlement = rail.route(quote.amount_inr, &quote.dest_currency, now)?;
+    let credit_date = state.calendar.credit_date(settlement.rail, &quote.source_currency, &quote.dest_currency, now, settlement.settles_at);
+    let mut body = serde_json::to_value(&quote).unwrap_or_default();
+    body["rate_age_secs"] = serde_json::json!(rate.age_secs(now));
+    body["rail"] = serde_json::json!(settlement.rail);
+    body["settles_at"] = serde_json::json!(settlement.settles_at);
+    body["expected_credit_date"] = serde_json::json!(credit_date);
+    body["credit_note"] = serde_json::json!(calendar::credit_note(credit_date, settlement.settles_at, now, state.calendar.timezone_for(&quote.dest_currency)));
+    Ok(Json(body).into_response())
+}
+
+/// The corridor matrix: which sources can pay out in each destination.
+async fn api_corridors(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let corridors: Vec<serde_json::Value> = state
+        .currencies
+        .matrix()
         .into_iter()
+        .map(|(dest, sources)| serde_json::json!({"dest": dest, "sources": sources}))
         .collect();
+    Json(serde_json::json!({ "corridors": corridors }))
+}
+
+/// Currencies payers can pay from or be paid in, with their ISO metadata and
+/// limits.
+async fn api_currencies(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let currencies: Vec<serde_json::Value> = state
+        .currencies
+        .sources()
+        .into_iter()
+        .chain(state.currencies.destinations().into_iter().filter(|d| !state.currencies.is_source(&d.code)))
+        .map(|c| {
+            let mut v = serde_json::to_value(c).unwrap_or_default();
+            v["payouts"] = serde_json::json!(state.currencies.payouts_for(&c.code));
+            v
+        })
+        .collect();
+    Json(serde_json::json!({ "currencies": currencies }))
+}
+
+#[derive(Deserialize)]
+struct FxHistoryQuery {
+    base: String,
+    quote: Option<String>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    interval: Option<String>,
+}
+
+/// OHLC candles from the recorded live rates (RFC3339 `from`/`to`, default
+/// the last 7 days; `interval` hour or day, default day).
+async fn api_fx_history(State(state): State<AppState>, Query(q): Query<FxHistoryQuery>) -> Result<Response, AppError> {
+    let base = q.base.trim().to_uppercase();
+    let quote = q.quote.as_deref().map(|c| c.trim().to_uppercase()).unwrap_or_else(|| "INR".into());
+    if state.currencies.get(&base).is_none() {
+        return Err(AppError::bad_request("base currency not supported"));
+    }
+    // fx_rates only holds rates to INR
+    if quote != "INR" {
+        return Err(AppError::bad_request("only quote=INR is recorded"));
+    }
+    let (interval, max_span) = match q.interval.as_deref().unwrap_or("day") {
+        "hour" => ("hour", chrono::Duration::days(31)),
+        "day" => ("day", chrono::Duration::days(366)),
+        _ => return Err(AppError::bad_request("interval must be hour or day")),
+    };
+    let to = q.to.unwrap_or_else(|| state.clock.now());
+    let from = q.from.unwrap_or(to - chrono::Duration::days(7));
+    if from >= to {
+        return Err(AppError::bad_request("from must be before to"));
+    }
+    if to - from > max_span {
+        return Err(AppError::bad_request(if interval == "hour" { "hourly history is limited to 31 days" } else { "daily history is limited to 366 days" }));
+    }
+    let candles = state.db.fx_candles(&base, &quote, from, to, interval).await?;
+    let change_pct = match (candles.first(), candles.last()) {
+        (Some(first), Some(last)) if candles.len() > 1 && first.open > 0.0 => Some(pricing::round2((last.close / first.open - 1.0) * 100.0)),
+        _ => None,
+    };
+    Ok(Json(serde_json::json!({
+        "base": base,
+        "quote": quote,
+        "interval": interval,
+        "from": from,
+        "to": to,
+        "change_pct": change_pct,
+        "candles": candles,
+    }))
+    .into_response())
+}
+
+#[derive(Deserialize)]
+struct AlertReq {
+    base: String,
+    quote: Option<String>,
+    threshold: f64,
+    direction: String,
+    channel: Option<String>,
+    contact: String,
+}
+
+/// Subscribe to a one-shot alert when base->quote goes above/below a threshold.
+/// The alert starts inactive: its contact gets a link to confirm it, and the
+/// response carries the `manage_token` for reading and cancelling it.
+async fn api_create_alert(State(state): State<AppState>, client: ClientInfo, Json(req): Json<AlertReq>) -> Result<Response, AppError> {
+    let base = req.base.trim().to_uppercase();
+    let quote = req.quote.as_deref().map(|c| c.trim().to_uppercase()).unwrap_or_else(|| "INR".into());
+    let direction = req.direction.trim().to_lowercase();
+    let channel = req.channel.as_deref().map(|c| c.trim().to_lowercase()).unwrap_or_else(|| "email".into());
+    let contact = req.contact.trim();
+    if base == "INR" || !state.currencies.is_source(&base) {
+        return Err(AppError::bad_request(format!("{} is not a supported currency", base)));
+    }
+    // Rates are only recorded to INR
+    if quote != "INR" {
+        return Err(AppError::bad_request("only quote=INR is supported"));
+    }
+    if !req.threshold.is_finite() || req.threshold <= 0.0 {
+        return Err(AppError::bad_request("threshold must be a positive rate"));
+    }
+    if !alerts::DIRECTIONS.contains(&direction.as_str()) {
+        return Err(AppError::bad_request("direction must be above or below"));
+    }
+    if !alerts::CHANNELS.contains(&channel.as_str()) || !state.notifier.supports(&channel) {
+        return Err(AppError::bad_request(format!("channel {} is not available", channel)));
+    }
+    if !alerts::valid_contact(&channel, contact) {
+        return Err(AppError::bad_request(format!("contact is not a valid {} address", channel)));
+    }
+    // Each confirmation is a message to someone: cap them per client and per contact
+    let now = state.clock.now();
+    let ip_key = format!("ip:{}", client.ip_string().unwrap_or_default());
+    let contact_key = format!("contact:{}", contact.to_lowercase());
+    if !state.alert_signups.allow(&ip_key, now) || !state.alert_signups.allow(&contact_key, now) {
+        return Err(ErrorKind::Throttled("alerts requested").into());
+    }
+    let confirm_token = alerts::new_token();
+    let manage_token = alerts::new_token();
+    let new = db::NewRateAlert {
+        base_currency: &base,
+        quote_currency: &quote,
+        threshold: req.threshold,
+        direction: &direction,
+        channel: &channel,
+        contact,
+        confirm_token_hash: &alerts::token_hash(&confirm_token),
+        manage_token_hash: &alerts::token_hash(&manage_token),
+    };
+    let alert = state.db.insert_rate_alert(&new).await?;
+    let link = format!("{}/api/v1/alerts/{}/confirm?token={}", base_url(), alert.id, confirm_token);
+    let (subject, body) = alerts::confirmation_message(&alert, &link);
+    if let Err(e) = state.notifier.send(&alert.channel, &alert.contact, &subject, &body).await {
+        let e = e.context(format!("rate alert {} confirmation via {}", alert.id, state.notifier.name()));
+        return Err(ErrorKind::Delivery(e).into());
+    }
+    let mut body = serde_json::to_value(&alert).unwrap_or_default();
+    body["manage_token"] = serde_json::Value::String(manage_token);
+    Ok((StatusCode::CREATED, Json(body)).into_response())
+}
+
+#[derive(Deserialize)]
+struct ConfirmQuery {
+    token: String,
+}
+
+/// The link in the confirmation message: switches the alert on.
+async fn api_confirm_alert(State(state): State<AppState>, Path(id): Path<Uuid>, Query(q): Query<ConfirmQuery>) -> Result<Response, AppError> {
+    let confirmed = state.db.confirm_rate_alert(id, &alerts::token_hash(q.token.trim()), state.clock.now() - alerts::CONFIRM_TTL).await?;
+    // Expired and already-used links look the same as unknown ones
+    let alert = confirmed.ok_or_else(|| AppError::not_found("pending alert"))?;
+    Ok(Html(format!("<p>Your {}/{} rate alert is on. We'll write once, when the rate goes {} {:.4}.</p>", alert.base_currency, alert.quote_currency, alert.direction, alert.threshold))
+        .into_response())
+}
+
+/// Hash of the `x-alert-token` header: the management token from subscribing.
+fn alert_token_hash(headers: &axum::http::HeaderMap) -> String {
+    alerts::token_hash(headers.get("x-alert-token").and_then(|v| v.to_str().ok()).map(str::trim).unwrap_or(""))
+}
+
+/// An alert's state, for the holder of its management token. A wrong token
+/// gets the same 404 as an unknown id.
+async fn api_get_alert(State(state): State<AppState>, Path(id): Path<Uuid>, headers: axum::http::HeaderMap) -> Result<Response, AppError> {
+    let alert = state.db.get_rate_alert(id, &alert_token_hash(&headers)).await?;
+    let alert = alert.ok_or_else(|| AppError::not_found("alert"))?;
+    Ok(Json(alert).into_response())
+}
+
+/// Unsubscribe, or withdraw an unconfirmed alert. Alerts that already fired
+/// or were cancelled, and wrong tokens, are a 404.
+async fn api_cancel_alert(State(state): State<AppState>, Path(id): Path<Uuid>, headers: axum::http::HeaderMap) -> Result<Response, AppError> {
+    if !state.db.cancel_rate_alert(id, &alert_token_hash(&headers)).await? {
+        return Err(AppError::not_found("active alert"));
+    }
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+async fn optimize_currency(State(state): State<AppState>, Query(q): Query<OptQuery>) -> Result<Response, AppError> {
+    let amount = q.amount.unwrap_or(0.0).max(0.0);
+    // The optimizer plans INR payouts, so it considers currencies that pay out in INR
+    let server_allowed: HashSet<String> = state.currencies.sources_for("INR").into_iter().collect();
 
     // Optional client filter (UI options) — intersect with server whitelist
     let client_allowed: Option<HashSet<String>> = q.allowed.as_deref().map(|allowed| allowed
@@ -372,31 +845,198 @@ async fn optimize_currency(Query(q): Query<OptQuery>) -> impl IntoResponse {
         None => server_allowed.into_iter().collect(),
     };
 
+    if let Some(target_inr) = q.amount_inr {
+        return optimize_for_target(&state, target_inr, &ccys_vec, &q).await;
+    }
     if ccys_vec.is_empty() {
-        return Json(serde_json::json!({
+        return Ok(Json(serde_json::json!({
             "best_currency": serde_json::Value::Null,
             "est_inr": 0.0,
             "assumption": "No allowed currencies configured",
             "items": []
-        }));
-    }
-    let mut best_ccy = "INR".to_string();
-    let mut best_inr = -1.0f64;
-    let mut items: Vec<serde_json::Value> = Vec::new();
-    for c in ccys_vec.iter() {
-        let rate = if c == "INR" { 1.0 } else { fallback_rate(c) };
-        let recv_inr = if c == "INR" { amount } else { (amount * rate - (99.0 + 25.0)).max(0.0) };
-        if recv_inr > best_inr { best_inr = recv_inr; best_ccy = c.to_string(); }
-        items.push(serde_json::json!({
-            "currency": c,
-            "rate": rate,
-            "est_inr": (recv_inr * 100.0).round() / 100.0
-        }));
+        })).into_response());
+    }
+    // Price every currency exactly like create_payment would: same cached
+    // rates, same fees added on top
+    let now = state.clock.now();
+    let bases: Vec<&str> = ccys_vec.iter().map(String::as_str).collect();
+    let rates = state.rates.rates_to_inr(&bases, now).await;
+    let mut quotes: Vec<(pricing::Quote, Option<i64>)> = ccys_vec
+        .iter()
+        .filter_map(|c| rates.get(c).and_then(|r| r.as_ref().ok()))
+        .map(|r| (pricing::Quote::forward(amount, r, state.currencies.precision(&r.base, "INR")), r.age_secs(now)))
+        .collect();
+    quotes.sort_by(|a, b| b.0.amount_inr.total_cmp(&a.0.amount_inr).then_with(|| a.0.source_currency.cmp(&b.0.source_currency)));
+    let items: Vec<serde_json::Value> = quotes
+        .iter()
+        .map(|(quote, age)| serde_json::json!({
+            "currency": quote.source_currency,
+            "rate": quote.rate,
+            "rate_provider": quote.rate_provider,
+            "rate_timestamp": quote.rate_timestamp,
+            "rate_age_secs": age,
+            "est_inr": quote.amount_inr,
+            "fee_src": quote.fee_src,
+            "total_src": quote.total_src,
+            "total_inr": quote.total_inr,
+        }))
+        .collect();
+    let best = quotes.first().map(|(q, _)| q);
+    Ok(Json(serde_json::json!({
+        "best_currency": best.map(|q| q.source_currency.clone()),
+        "est_inr": best.map(|q| q.amount_inr).unwrap_or(0.0),
+        "assumption": "Same numeric amount in each currency, priced like a real payment: the receiver gets amount × rate and fees are added on top at the same rate.",
+        "items": items,
+        "unavailable": unavailable_rates(&rates),
+    })).into_response())
+}
+
+/// Currencies left out for lack of an acceptable rate, with the reason.
+fn unavailable_rates(rates: &std::collections::HashMap<String, Result<crate::fx::Rate, crate::fx::RateError>>) -> Vec<serde_json::Value> {
+    let mut out: Vec<(&String, &crate::fx::RateError)> = rates.iter().filter_map(|(c, r)| r.as_ref().err().map(|e| (c, e))).collect();
+    out.sort_by(|a, b| a.0.cmp(b.0));
+    out.into_iter().map(|(c, e)| serde_json::json!({"currency": c, "code": e.code(), "error": e.to_string()})).collect()
+}
+
+/// Plans delivering `target_inr` from the payer's balances, cheapest first in
+/// the reference currency.
+async fn optimize_for_target(state: &AppState, target_inr: f64, allowed: &[String], q: &OptQuery) -> Result<Response, AppError> {
+    if !target_inr.is_finite() || target_inr < 0.01 {
+        return Err(AppError::bad_request("amount_inr must be at least 0.01"));
+    }
+    let balances = q.balances.as_deref().map(optimizer::parse_balances).transpose().map_err(AppError::bad_request)?.filter(|b| !b.is_empty());
+    if let Some(b) = balances.iter().flatten().find(|b| !allowed.contains(&b.currency)) {
+        return Err(AppError::bad_request(format!("{} is not a supported currency", b.currency)));
+    }
+    let reference = q.reference.as_deref().map(|r| r.trim().to_uppercase()).unwrap_or_else(|| "INR".into());
+    if state.currencies.get(&reference).is_none() {
+        return Err(AppError::bad_request(format!("{} is not a supported reference currency", reference)));
+    }
+
+    let held: Vec<(String, Option<f64>)> = match &balances {
+        Some(b) => b.iter().map(|b| (b.currency.clone(), Some(b.amount))).collect(),
+        None => {
+            let mut all: Vec<String> = allowed.to_vec();
+            all.sort();
+            all.into_iter().map(|c| (c, None)).collect()
+        }
+    };
+    let now = state.clock.now();
+    let mut bases: Vec<&str> = held.iter().map(|(c, _)| c.as_str()).collect();
+    bases.push(&reference);
+    let rates = state.rates.rates_to_inr(&bases, now).await;
+    let holdings: Vec<optimizer::Holding> = held
+        .iter()
+        .filter_map(|(c, balance)| rates.get(c).and_then(|r| r.as_ref().ok()).map(|r| optimizer::Holding {
+            balance: *balance,
+            rate: r.clone(),
+            precision: state.currencies.precision(c, "INR"),
+        }))
+        .collect();
+    let reference_rate = match rates.get(&reference) {
+        Some(Ok(r)) => r,
+        Some(Err(e)) => return Err(e.clone().into()),
+        None => return Err(AppError::bad_request(format!("No rate for {}", reference))),
+    };
+
+    // Each leg is its own payment, so each must be within its currency's limits
+    let plans: Vec<optimizer::Plan> = optimizer::plans(target_inr, &holdings, reference_rate)
+        .map_err(AppError::bad_request)?
+        .into_iter()
+        .filter(|p| p.legs.iter().all(|l| state.limits.check_quote(&l.quote, &state.currencies).is_ok()))
+        .collect();
+    let mut body = serde_json::json!({
+        "target_inr": pricing::round2(target_inr),
+        "reference": reference,
+        "plans": plans.iter().take(10).collect::<Vec<_>>(),
+        "assumption": "Each currency used is a separate payment with its own fees, priced like a real payment at the same cached rates. Costs are what leaves your balances, valued in the reference currency.",
+        "unavailable": unavailable_rates(&rates),
+    });
+    if balances.is_some() {
+        let mut capacities: Vec<f64> = holdings.iter().map(|h| h.capacity_inr()).collect();
+        capacities.sort_by(|a, b| b.total_cmp(a));
+        let available: f64 = capacities.iter().take(optimizer::MAX_LEGS).sum();
+        body["available_inr"] = serde_json::json!(pricing::round2(available));
+        if plans.is_empty() {
+            body["shortfall"] = serde_json::json!(format!(
+                "Your balances can deliver at most ₹{:.2} (in up to {} payments after fees).",
+                available,
+                optimizer::MAX_LEGS
+            ));
+        }
+    }
+    Ok(Json(body).into_response())
+}
+
+pub fn reasons_json(hits: &[ai::ReasonHit], lang: &str) -> serde_json::Value {
+    serde_json::Value::Array(hits.iter().map(|h| serde_json::json!({
+        "code": h.code.code(),
+        "points": h.points,
+        "text": h.code.text(lang),
+    })).collect())
+}
+
+#[derive(Deserialize)]
+struct PaymentQuery {
+    lang: Option<String>,
+    /// The session that created the payment; required.
+    sid: Option<Uuid>,
+}
+
+/// A payment as seen by the session that created it. Any other session, or
+/// none, gets the same 404 as an unknown id so ids can't be probed.
+async fn api_get_payment(State(state): State<AppState>, Path(id): Path<Uuid>, Query(q): Query<PaymentQuery>) -> Response {
+    // Reason texts exist for every assistant language; anything else is English
+    let lang = q.lang.as_deref().and_then(lang::supported).unwrap_or("en");
+    let not_found = || (StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": "payment not found" }))).into_response();
+    let owned = match q.sid {
+        Some(sid) => state.db.get_session(sid).await.ok().flatten().is_some_and(|s| s.payment_id == Some(id)),
+        None => false,
+    };
+    if !owned {
+        return not_found();
+    }
+    match state.db.get_payment(id).await {
+        Ok(Some(p)) => Json(serde_json::json!({
+            "lang": lang,
+            "id": p.id,
+            "status": p.status,
+            "created_at": p.created_at,
+            "payer_name": p.payer_name,
+            "sender_name": p.sender_name,
+            "upi_id": p.upi_id,
+            "rail": p.rail,
+            "settles_at": p.settles_at,
+            "expected_credit_date": p.expected_credit_date,
+            "ip_country": p.ip_country,
+            "payee_lookup": p.payee_lookup,
+            "payee_name_masked": p.payee_registered_name.as_deref().map(rails::mask_name),
+            "source_currency": p.source_currency,
+            "source_amount": p.source_amount,
+            "dest_currency": p.dest_currency,
+            "dest_amount": p.dest_amount,
+            "rate_to_dest": p.rate_to_dest,
+            "amount_inr": p.amount_inr,
+            "rate_to_inr": p.rate_to_inr,
+            "rate_timestamp": p.rate_timestamp,
+            "rate_provider": p.rate_provider,
+            "fees": {
+                "transfer_inr": p.fee_transfer_inr,
+                "platform_inr": p.fee_platform_inr,
+                "total_src": p.fee_src_total,
+            },
+            "total_inr": p.total_inr,
+            "total_src": p.total_src,
+            "risk": {
+                "score": p.risk_score,
+                "label": p.risk_label,
+                "reasons": reasons_json(&p.risk_reason_codes, lang),
+            },
+        })).into_response(),
+        Ok(None) => not_found(),
+        Err(e) => {
+            tracing::error!(error = %e, "payment lookup failed");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": "database error" }))).into_response()
+        }
     }
-    Json(serde_json::json!({
-        "best_currency": best_ccy,
-        "est_inr": (best_inr * 100.0).round() / 100.0,
-        "assumption": "Same numeric amount across currencies; non-INR includes ₹124 fees using fallback rates.",
-        "items": items
-    }))
 }
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Utc};
+
+// In-memory request budgets for public endpoints that reveal something per
+// call (payee lookups) or cost something (alert emails): each key, usually a
+// client IP, gets `limit` calls per fixed window. State is per process and
+// lost on restart, which is fine for slowing down enumeration and spam.
+
+/// Forget idle keys once this many are tracked.
+const PRUNE_AT: usize = 10_000;
+
+pub struct Throttle {
+    limit: u32,
+    window: Duration,
+    /// Window start and calls made in it, per key.
+    hits: Mutex<HashMap<String, (DateTime<Utc>, u32)>>,
+}
+
+impl Throttle {
+    pub fn new(limit: u32, window: Duration) -> Self {
+        Throttle { limit, window, hits: Mutex::new(HashMap::new()) }
+    }
+
+    /// `limit` from env var `var` (default `default`) per `window`.
+    pub fn from_env(var: &str, default: u32, window: Duration) -> anyhow::Result<Self> {
+        let limit = match std::env::var(var) {
+            Ok(raw) if !raw.trim().is_empty() => raw.trim().parse().map_err(|_| anyhow::anyhow!("{} must be a whole number", var))?,
+            _ => default,
+        };
+        Ok(Self::new(limit, window))
+    }
+
+    /// Count a call from `key` at `now`; false once `key` has used its
+    /// budget for the current window.
+    pub fn allow(&self, key: &str, now: DateTime<Utc>) -> bool {
+        let mut hits = self.hits.lock().unwrap_or_else(|e| e.into_inner());
+        if hits.len() >= PRUNE_AT {
+            hits.retain(|_, (start, _)| now - *start < self.window);
+        }
+        let entry = hits.entry(key.to_string()).or_insert((now, 0));
+        if now - entry.0 >= self.window {
+            *entry = (now, 0);
+        }
+        if entry.1 >= self.limit {
+            return false;
+        }
+        entry.1 += 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn each_key_gets_its_own_budget() {
+        let throttle = Throttle::new(2, Duration::minutes(1));
+        let now = at("2024-09-11T10:00:00Z");
+        assert!(throttle.allow("10.0.0.1", now));
+        assert!(throttle.allow("10.0.0.1", now));
+        assert!(!throttle.allow("10.0.0.1", now));
+        assert!(throttle.allow("10.0.0.2", now));
+    }
+
+    #[test]
+    fn the_budget_resets_when_the_window_ends() {
+        let throttle = Throttle::new(1, Duration::minutes(1));
+        assert!(throttle.allow("ip", at("2024-09-11T10:00:00Z")));
+        assert!(!throttle.allow("ip", at("2024-09-11T10:00:59Z")));
+        assert!(throttle.allow("ip", at("2024-09-11T10:01:00Z")));
+    }
+
+    #[test]
+    fn a_zero_limit_refuses_everything() {
+        assert!(!Throttle::new(0, Duration::minutes(1)).allow("ip", at("2024-09-11T10:00:00Z")));
+    }
+}
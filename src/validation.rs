@@ -0,0 +1,309 @@
+use serde::{Deserialize, Serialize};
+
+use crate::currency::Registry;
+use crate::payee::{self, Payee};
+use crate::pricing::QuoteMode;
+
+// Validation of what payers submit. Each problem is reported against the
+// form field it came from, so pay_form.html can show it next to the input;
+// all problems are collected rather than stopping at the first.
+
+pub const MAX_NAME_CHARS: usize = 100;
+/// UPI caps the transaction note at 50 characters.
+pub const MAX_NOTE_CHARS: usize = 50;
+
+// Missing or malformed fields deserialize anyway so they are reported as
+// field errors instead of a bare extractor rejection.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PaymentForm {
+    /// The receiver's name (the field predates `sender_name`).
+    #[serde(default)]
+    pub payer_name: String,
+    /// The payer's own name; optional, for clients that predate it.
+    #[serde(default)]
+    pub sender_name: Option<String>,
+    #[serde(default)]
+    pub upi_or_mobile: String,
+    /// Amount to send, in `currency`.
+    #[serde(default, deserialize_with = "blank_or_amount")]
+    pub amount: Option<f64>,
+    /// Or: what the receiver should get in `dest_currency`; the source amount
+    /// is derived.
+    #[serde(default, deserialize_with = "blank_or_amount")]
+    pub dest_amount: Option<f64>,
+    /// Older name for `dest_amount` on INR payouts.
+    #[serde(default, deserialize_with = "blank_or_amount")]
+    pub amount_inr: Option<f64>,
+    #[serde(default)]
+    pub currency: String,
+    /// Payout currency (default INR).
+    pub dest_currency: Option<String>,
+    pub note: Option<String>,
+    pub sid: Option<String>,
+    pub tz: Option<String>,
+    pub device_fp: Option<String>,
+}
+
+impl PaymentForm {
+    /// What was typed, to refill the payer form after an error.
+    pub fn echo(&self) -> serde_json::Value {
+        let (mode, amount) = match (self.amount, self.dest_amount.or(self.amount_inr)) {
+            (None, Some(a)) => ("receive", Some(a)),
+            (a, _) => ("send", a),
+        };
+        serde_json::json!({
+            "payer_name": self.payer_name,
+            "sender_name": self.sender_name,
+            "upi_or_mobile": self.upi_or_mobile,
+            "mode": mode,
+            "amount": amount.filter(|a| a.is_finite()),
+            "currency": self.currency.trim().to_uppercase(),
+            "dest_currency": self.dest_currency.as_deref().map(|c| c.trim().to_uppercase()),
+            "note": self.note,
+        })
+    }
+}
+
+/// An empty amount is absent; one that isn't a number is NaN, which
+/// `amount_spec` rejects.
+fn blank_or_amount<'de, D: serde::Deserializer<'de>>(de: D) -> Result<Option<f64>, D::Error> {
+    let raw: Option<String> = Option::deserialize(de)?;
+    Ok(raw.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).map(|s| s.parse().unwrap_or(f64::NAN)))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    /// Form field name, e.g. "upi_or_mobile".
+    pub field: &'static str,
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl FieldError {
+    fn new(field: &'static str, code: &'static str, message: impl Into<String>) -> Self {
+        FieldError { field, code, message: message.into() }
+    }
+}
+
+/// A payment form that passed validation, normalized.
+#[derive(Debug, Clone)]
+pub struct ValidPayment {
+    /// The receiver's name.
+    pub payer_name: String,
+    /// None when the payer didn't give it.
+    pub sender_name: Option<String>,
+    pub payee: Payee,
+    /// `payee.address()`.
+    pub upi_id: String,
+    pub mode: QuoteMode,
+    /// Source amount (forward) or receiver credit (reverse).
+    pub value: f64,
+    pub source_currency: String,
+    pub dest_currency: String,
+    pub note: Option<String>,
+}
+
+pub fn payment_form(form: &PaymentForm, currencies: &Registry, payees: &payee::Parser) -> Result<ValidPayment, Vec<FieldError>> {
+    let mut errors = Vec::new();
+
+    let payer_name = name("payer_name", &form.payer_name, &mut errors);
+    let sender_name = form.sender_name.as_deref().filter(|n| !n.trim().is_empty()).map(|n| name("sender_name", n, &mut errors));
+
+    let payee = payees.parse(&form.upi_or_mobile);
+    if let Err(e) = &payee {
+        errors.push(FieldError::new("upi_or_mobile", e.code(), e.to_string()));
+    }
+
+    let source_currency = form.currency.trim().to_uppercase();
+    let dest_currency = form.dest_currency.as_deref().map(|c| c.trim().to_uppercase()).filter(|c| !c.is_empty()).unwrap_or_else(|| "INR".into());
+    if !currencies.is_source(&source_currency) {
+        errors.push(FieldError::new("currency", "currency_unsupported", format!("{} is not a supported currency", source_currency)));
+    } else if !currencies.allows(&source_currency, &dest_currency) {
+        errors.push(FieldError::new("dest_currency", "corridor_unavailable", format!("Payments from {} to {} are not available", source_currency, dest_currency)));
+    }
+
+    let amount_field = match (form.amount, form.dest_amount, form.amount_inr) {
+        (None, Some(_), _) => "dest_amount",
+        (None, None, Some(_)) => "amount_inr",
+        _ => "amount",
+    };
+    let spec = receive_amount(&dest_currency, form.dest_amount, form.amount_inr).and_then(|receive| amount_spec(form.amount, receive));
+    if let Err(msg) = spec {
+        errors.push(FieldError::new(amount_field, "amount_invalid", msg));
+    }
+
+    let note = form.note.as_deref().map(str::trim).filter(|n| !n.is_empty()).map(str::to_string);
+    if let Some(n) = &note {
+        if n.chars().count() > MAX_NOTE_CHARS {
+            errors.push(FieldError::new("note", "note_too_long", format!("Notes can be at most {} characters", MAX_NOTE_CHARS)));
+        } else if n.chars().any(char::is_control) {
+            errors.push(FieldError::new("note", "note_invalid", "Notes can't contain line breaks or control characters"));
+        }
+    }
+
+    match (spec, payee) {
+        (Ok((mode, value)), Ok(payee)) if errors.is_empty() => {
+            Ok(ValidPayment { payer_name, sender_name, upi_id: payee.address(), payee, mode, value, source_currency, dest_currency, note })
+        }
+        _ => Err(errors),
+    }
+}
+
+/// A person's name with runs of whitespace collapsed.
+fn name(field: &'static str, raw: &str, errors: &mut Vec<FieldError>) -> String {
+    let name = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.chars().count() < 2 || !name.chars().any(char::is_alphabetic) {
+        errors.push(FieldError::new(field, "name_required", "Enter a name of at least 2 letters"));
+    } else if name.chars().count() > MAX_NAME_CHARS {
+        errors.push(FieldError::new(field, "name_too_long", format!("Names can be at most {} characters", MAX_NAME_CHARS)));
+    }
+    name
+}
+
+/// The receiver-side amount of a reverse quote: `dest_amount`, or the older
+/// `amount_inr` for INR payouts.
+pub fn receive_amount(dest: &str, dest_amount: Option<f64>, amount_inr: Option<f64>) -> Result<Option<f64>, &'static str> {
+    match (dest_amount, amount_inr) {
+        (Some(_), Some(_)) => Err("Give either dest_amount or amount_inr, not both"),
+        (None, Some(_)) if dest != "INR" => Err("amount_inr only applies to INR payouts; use dest_amount"),
+        (d, a) => Ok(d.or(a)),
+    }
+}
+
+/// Which way a payment or quote is priced: from the source amount, or from
+/// the receiver's credit. Exactly one must be given.
+pub fn amount_spec(amount: Option<f64>, dest_amount: Option<f64>) -> Result<(QuoteMode, f64), &'static str> {
+    let (mode, value) = match (amount, dest_amount) {
+        (Some(a), None) => (QuoteMode::Forward, a),
+        (None, Some(a)) => (QuoteMode::Reverse, a),
+        (None, None) => return Err("Enter an amount to send or the amount the receiver should get"),
+        (Some(_), Some(_)) => return Err("Give either the amount to send or the amount to receive, not both"),
+    };
+    if value.is_nan() {
+        return Err("Amount must be a number");
+    }
+    if !value.is_finite() || value < 0.01 {
+        return Err("Amount must be at least 0.01");
+    }
+    Ok((mode, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::currency::Currency;
+
+    fn registry() -> Registry {
+        let currency = |code: &str, payouts: &[&str]| -> Currency {
+            serde_json::from_value(serde_json::json!({
+                "code": code, "name": code, "country": code, "flag": "", "symbol": code,
+                "minor_units": 2, "min_amount": 1, "max_amount": 50000, "payouts": payouts,
+            }))
+            .unwrap()
+        };
+        let currencies = vec![currency("INR", &["INR"]), currency("AED", &["INR", "NPR"]), currency("EUR", &["INR"]), currency("NPR", &[])];
+        Registry::build(currencies, None, None).unwrap()
+    }
+
+    fn form() -> PaymentForm {
+        PaymentForm {
+            payer_name: "Priya Sharma".into(),
+            sender_name: Some("Arjun  Mehta".into()),
+            upi_or_mobile: "priya@oksbi".into(),
+            amount: Some(100.0),
+            dest_amount: None,
+            amount_inr: None,
+            currency: "aed".into(),
+            dest_currency: None,
+            note: Some("  rent  ".into()),
+            sid: None,
+            tz: None,
+            device_fp: None,
+        }
+    }
+
+    fn validate(form: &PaymentForm) -> Result<ValidPayment, Vec<FieldError>> {
+        payment_form(form, &registry(), &payee::Parser::default())
+    }
+
+    fn fields(errors: &[FieldError]) -> Vec<(&str, &str)> {
+        errors.iter().map(|e| (e.field, e.code)).collect()
+    }
+
+    #[test]
+    fn a_valid_form_is_normalized() {
+        let valid = validate(&form()).unwrap();
+        assert_eq!(valid.sender_name.as_deref(), Some("Arjun Mehta"));
+        assert_eq!(valid.upi_id, "priya@oksbi");
+        assert_eq!(valid.source_currency, "AED");
+        assert_eq!(valid.dest_currency, "INR");
+        assert_eq!((valid.mode, valid.value), (QuoteMode::Forward, 100.0));
+        assert_eq!(valid.note.as_deref(), Some("rent"));
+    }
+
+    #[test]
+    fn sender_name_is_optional() {
+        assert_eq!(validate(&PaymentForm { sender_name: None, ..form() }).unwrap().sender_name, None);
+        assert_eq!(validate(&PaymentForm { sender_name: Some("  ".into()), ..form() }).unwrap().sender_name, None);
+    }
+
+    #[test]
+    fn receive_amounts_price_in_reverse() {
+        let valid = validate(&PaymentForm { amount: None, dest_amount: Some(3_600.0), dest_currency: Some("npr".into()), ..form() }).unwrap();
+        assert_eq!((valid.mode, valid.value), (QuoteMode::Reverse, 3_600.0));
+        assert_eq!(valid.dest_currency, "NPR");
+        let valid = validate(&PaymentForm { amount: None, amount_inr: Some(10_000.0), ..form() }).unwrap();
+        assert_eq!((valid.mode, valid.value), (QuoteMode::Reverse, 10_000.0));
+    }
+
+    #[test]
+    fn every_problem_is_reported_against_its_field() {
+        let bad = PaymentForm {
+            payer_name: " ".into(),
+            sender_name: Some("12".into()),
+            upi_or_mobile: "priya@nowhere".into(),
+            amount: Some(f64::NAN),
+            currency: "XYZ".into(),
+            note: Some("line\nbreak".into()),
+            ..form()
+        };
+        let errors = validate(&bad).unwrap_err();
+        assert_eq!(
+            fields(&errors),
+            [
+                ("payer_name", "name_required"),
+                ("sender_name", "name_required"),
+                ("upi_or_mobile", "vpa_unknown_handle"),
+                ("currency", "currency_unsupported"),
+                ("amount", "amount_invalid"),
+                ("note", "note_invalid"),
+            ]
+        );
+    }
+
+    #[test]
+    fn corridors_and_amount_modes_are_checked() {
+        let errors = validate(&PaymentForm { currency: "EUR".into(), dest_currency: Some("NPR".into()), ..form() }).unwrap_err();
+        assert_eq!(fields(&errors), [("dest_currency", "corridor_unavailable")]);
+        let errors = validate(&PaymentForm { dest_amount: Some(10.0), ..form() }).unwrap_err();
+        assert_eq!(fields(&errors), [("amount", "amount_invalid")]);
+        let errors = validate(&PaymentForm { amount: None, ..form() }).unwrap_err();
+        assert_eq!(fields(&errors), [("amount", "amount_invalid")]);
+        let errors = validate(&PaymentForm { amount: None, amount_inr: Some(10.0), dest_currency: Some("NPR".into()), ..form() }).unwrap_err();
+        assert_eq!(fields(&errors), [("amount_inr", "amount_invalid")]);
+    }
+
+    #[test]
+    fn name_and_note_lengths() {
+        let long = "a".repeat(MAX_NAME_CHARS + 1);
+        let errors = validate(&PaymentForm { payer_name: long, note: Some("n".repeat(MAX_NOTE_CHARS + 1)), ..form() }).unwrap_err();
+        assert_eq!(fields(&errors), [("payer_name", "name_too_long"), ("note", "note_too_long")]);
+    }
+
+    #[test]
+    fn amounts_must_be_positive_numbers() {
+        assert_eq!(amount_spec(Some(0.001), None), Err("Amount must be at least 0.01"));
+        assert_eq!(amount_spec(Some(f64::INFINITY), None), Err("Amount must be at least 0.01"));
+        assert_eq!(amount_spec(None, Some(0.01)), Ok((QuoteMode::Reverse, 0.01)));
+    }
+}
@@ -0,0 +1,20 @@
+// Payment Q&A on the success page (kept out of inline script for the page's CSP).
+// The session ID proves ownership; /ask answers from that payment's record.
+(function(){
+  var box = document.getElementById('payAsk');
+  if (!box) return;
+  var q = document.getElementById('payAskQ');
+  var a = document.getElementById('payAskA');
+  var btn = document.getElementById('payAskBtn');
+  function ask(){
+    var question = (q.value || '').trim();
+    if (!question) return;
+    a.textContent = 'Thinking…';
+    fetch('/ask', { method: 'POST', headers: { 'content-type': 'application/json' }, body: JSON.stringify({ question: question, session_id: box.dataset.sid, lang: navigator.language }) })
+      .then(function(r){ return r.json(); })
+      .then(function(j){ a.textContent = (j && (j.answer || j.error)) || 'Sorry, no answer.'; })
+      .catch(function(){ a.textContent = 'Sorry, something went wrong.'; });
+  }
+  btn.addEventListener('click', ask);
+  q.addEventListener('keydown', function(e){ if (e.key === 'Enter') ask(); });
+})();
@@ -0,0 +1,75 @@
+// Jet whoosh on the success page using Web Audio (no external file required),
+// kept out of inline script for the page's CSP.
+function playJetWhoosh() {
+  try {
+    const AudioContext = window.AudioContext || window.webkitAudioContext;
+    if (!AudioContext) return;
+    const ctx = new AudioContext();
+    const dur = 1.6; // seconds (longer)
+    const sr = ctx.sampleRate;
+    const length = Math.floor(sr * dur);
+    const buffer = ctx.createBuffer(2, length, sr);
+    for (let ch = 0; ch < 2; ch++) {
+      const data = buffer.getChannelData(ch);
+      for (let i = 0; i < length; i++) {
+        // pinkish noise
+        const white = Math.random() * 2 - 1;
+        data[i] = (data[i-1] || 0) * 0.98 + white * 0.06;
+        // envelope: fast attack, quick decay
+        const t = i / sr;
+        const env = Math.min(1, t * 10) * Math.exp(-2.4 * t);
+        data[i] *= env;
+      }
+    }
+    const src = ctx.createBufferSource();
+    src.buffer = buffer;
+    // Filter sweep to simulate jet pass-by
+    const filter = ctx.createBiquadFilter();
+    filter.type = 'bandpass';
+    filter.frequency.value = 800;
+    const gain = ctx.createGain();
+    gain.gain.value = 0.45; // a bit louder
+    const panner = ctx.createStereoPanner ? ctx.createStereoPanner() : null;
+    if (panner) panner.pan.value = -0.8;
+    src.connect(filter);
+    filter.connect(panner || gain);
+    if (panner) panner.connect(gain);
+    gain.connect(ctx.destination);
+    src.start();
+    // Animate sweep and pan
+    const now = ctx.currentTime;
+    filter.frequency.setValueAtTime(600, now);
+    filter.frequency.exponentialRampToValueAtTime(2600, now + 0.45);
+    filter.frequency.exponentialRampToValueAtTime(500, now + 1.4);
+    if (panner) {
+      panner.pan.setValueAtTime(-0.8, now);
+      panner.pan.linearRampToValueAtTime(0.8, now + 0.9);
+    }
+    // Add a short, soft chime overlay (UPI-like confirmation feel)
+    const osc = ctx.createOscillator();
+    osc.type = 'sine';
+    osc.frequency.setValueAtTime(880, now + 0.15);
+    osc.frequency.exponentialRampToValueAtTime(1320, now + 0.35);
+    const g2 = ctx.createGain();
+    g2.gain.setValueAtTime(0.0001, now + 0.15);
+    g2.gain.exponentialRampToValueAtTime(0.35, now + 0.20);
+    g2.gain.exponentialRampToValueAtTime(0.0001, now + 0.75);
+    osc.connect(g2); g2.connect(ctx.destination);
+    osc.start(now + 0.15);
+    osc.stop(now + 0.9);
+    // Auto-close context to release resources
+    setTimeout(() => { try { ctx.close(); } catch(e){} }, (dur + 0.4) * 1000);
+  } catch(e) { /* ignore */ }
+}
+// Try autoplay on load (armed from previous user gesture), retry briefly if blocked, with interaction fallbacks
+(function(){
+  let played = false;
+  function tryPlay(){ if (!played) { playJetWhoosh(); played = true; } }
+  function armed(){ try { return sessionStorage.getItem('gp_sound') === '1'; } catch(e){ return false; } }
+  function disarm(){ try { sessionStorage.removeItem('gp_sound'); } catch(e){} }
+  function attemptSequence(){ if (!armed()) return; tryPlay(); setTimeout(tryPlay, 150); setTimeout(tryPlay, 400); setTimeout(tryPlay, 900); setTimeout(disarm, 1200); }
+  window.addEventListener('DOMContentLoaded', attemptSequence);
+  document.addEventListener('visibilitychange', function(){ if (!document.hidden) attemptSequence(); });
+  document.addEventListener('click', tryPlay, { once: true });
+  document.addEventListener('touchstart', tryPlay, { once: true, passive: true });
+})();